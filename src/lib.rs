@@ -1,25 +1,74 @@
 use arc_gc::arc::GCArcWeak;
 use arc_gc::traceable::GCTraceable;
+use indexmap::IndexMap;
 use onion_frontend::dir_stack::DirectoryStack;
 use onion_vm::lambda::runnable::RuntimeError;
+use onion_vm::types::lambda::vm_instructions::instruction_set::VMInstructionPackage;
 use onion_vm::types::named::OnionNamed;
 use onion_vm::types::object::{OnionObject, OnionObjectCell, OnionObjectExt, OnionStaticObject};
 use onion_vm::types::pair::OnionPair;
 // 引入 RuntimeError
 use onion_vm::types::tuple::OnionTuple;
-use pyo3::exceptions::PyTypeError; // 引入 PyTypeError
-use pyo3::types::PyAny;
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyTypeError, PyValueError}; // 引入 Python 异常类型
+use pyo3::types::{PyAny, PyBytes, PyDict, PyList, PyTuple};
 use pyo3::{prelude::*, IntoPyObjectExt};
 use pyo3_async_runtimes::tokio::future_into_py;
+use script::stdlib::{build_named_dict, pretty_string};
 use std::fmt::Debug;
 use std::sync::Arc;
 
 mod pycallable;
 mod script;
 
+create_exception!(
+    onion,
+    OnionError,
+    PyException,
+    "Base class for errors raised by the Onion runtime."
+);
+create_exception!(
+    onion,
+    OnionPending,
+    OnionError,
+    "Raised when an Onion operation is still pending."
+);
+create_exception!(
+    onion,
+    OnionTimeout,
+    OnionError,
+    "Raised when an Onion script exceeds its time budget."
+);
+create_exception!(
+    onion,
+    OnionStepLimitExceeded,
+    OnionError,
+    "Raised when an Onion script exceeds its step budget."
+);
+
 // Helper function to convert RuntimeError to PyErr
 fn runtime_error_to_pyerr(err: RuntimeError) -> PyErr {
-    PyTypeError::new_err(err.to_string()) // 将 Runtime Error 转换为 Python 的 TypeError
+    let message = err.to_string();
+    match err {
+        RuntimeError::InvalidType(_) => PyTypeError::new_err(message),
+        RuntimeError::InvalidOperation(_) => PyValueError::new_err(message),
+        RuntimeError::Pending => OnionPending::new_err(message),
+        _ => OnionError::new_err(message),
+    }
+}
+
+// Helper function to convert a failed script run to PyErr
+fn execution_error_to_pyerr(err: script::ExecutionError) -> PyErr {
+    match err {
+        script::ExecutionError::Timeout(_) => OnionTimeout::new_err(err.to_string()),
+        script::ExecutionError::StepLimitExceeded(_) => {
+            OnionStepLimitExceeded::new_err(err.to_string())
+        }
+        _ => PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+            "Failed to evaluate script: {}",
+            err
+        )),
+    }
 }
 
 fn pyerr_to_runtime_error(e: PyErr, py: Python<'_>) -> RuntimeError {
@@ -244,11 +293,45 @@ impl PyOnionObject {
             .map_err(runtime_error_to_pyerr)
     }
 
+    // 仅接受由 Named 组成的 Tuple，递归转换为 Python dict
+    fn as_dict(&self, py: Python) -> PyResult<PyObject> {
+        let data = self
+            .inner
+            .weak()
+            .with_data(|obj| Ok(obj.clone()))
+            .map_err(runtime_error_to_pyerr)?;
+        let OnionObject::Tuple(tuple) = &data else {
+            return Err(PyTypeError::new_err(format!(
+                "Object is not a dict of Named entries: {:?}",
+                data
+            )));
+        };
+        if let Some(element) = tuple
+            .get_elements()
+            .iter()
+            .find(|element| !matches!(element, OnionObject::Named(_)))
+        {
+            return Err(PyTypeError::new_err(format!(
+                "Dict entry is not a Named: {:?}",
+                element
+            )));
+        }
+        named_elements_to_py_dict(py, tuple.get_elements())
+    }
+
     // --- 核心操作方法 ---
     fn type_name(&self) -> PyResult<String> {
         self.inner.weak().type_of().map_err(runtime_error_to_pyerr)
     }
 
+    fn type_matches(&self, name: &str) -> PyResult<bool> {
+        self.inner
+            .weak()
+            .type_of()
+            .map(|type_name| type_name == name)
+            .map_err(runtime_error_to_pyerr)
+    }
+
     // 实现 Python 的 __repr__ 和 __str__
     fn __repr__(&self) -> PyResult<String> {
         // repr 方法需要一个 ptrs 参数，这里传递一个空 Vec
@@ -266,6 +349,41 @@ impl PyOnionObject {
             .map_err(runtime_error_to_pyerr)
     }
 
+    // 以缩进树的形式渲染嵌套结构，便于调试
+    #[pyo3(signature = (indent = 2))]
+    fn pretty(&self, indent: usize) -> PyResult<String> {
+        self.inner
+            .weak()
+            .with_data(|obj| pretty_string(obj, indent, 0))
+            .map_err(runtime_error_to_pyerr)
+    }
+
+    // 对 Tuple 的每个元素调用 Python 回调，返回由结果组成的新 Tuple
+    fn map_py(&self, callback: PyObject, py: Python) -> PyResult<Self> {
+        let elements = self
+            .inner
+            .weak()
+            .with_data(|obj| match obj {
+                OnionObject::Tuple(tuple) => Ok(tuple
+                    .get_elements()
+                    .iter()
+                    .map(|element| element.stabilize())
+                    .collect::<Vec<_>>()),
+                _ => Err(RuntimeError::InvalidType(
+                    format!("Object is not a Tuple: {:?}", obj).into(),
+                )),
+            })
+            .map_err(runtime_error_to_pyerr)?;
+        let mut mapped = Vec::with_capacity(elements.len());
+        for element in elements {
+            let result = callback.call1(py, (PyOnionObject::from_rust(element),))?;
+            mapped.push(py_object_to_onion_object(py, result)?);
+        }
+        Ok(PyOnionObject::from_rust(OnionTuple::new_static_no_ref(
+            &mapped,
+        )))
+    }
+
     fn len(&self) -> PyResult<Self> {
         self.inner
             .weak()
@@ -301,6 +419,60 @@ impl PyOnionObject {
             .map_err(runtime_error_to_pyerr)
     }
 
+    // Implement Python's __iter__，仅 Tuple 和 Range 可迭代
+    fn __iter__(&self) -> PyResult<PyOnionObjectIterator> {
+        let iterable = self
+            .inner
+            .weak()
+            .with_data(|obj| {
+                Ok(matches!(
+                    obj,
+                    OnionObject::Tuple(_) | OnionObject::Range(_, _)
+                ))
+            })
+            .map_err(runtime_error_to_pyerr)?;
+        if !iterable {
+            return Err(PyTypeError::new_err(format!(
+                "'{}' object is not iterable",
+                self.inner
+                    .weak()
+                    .type_of()
+                    .map_err(runtime_error_to_pyerr)?
+            )));
+        }
+        Ok(PyOnionObjectIterator {
+            inner: self.inner.clone(),
+            index: 0,
+            len: self.__len__()?,
+        })
+    }
+
+    // Implement Python's __call__，同步调用 Onion lambda
+    #[pyo3(signature = (*args, **kwargs))]
+    fn __call__(
+        &self,
+        py: Python,
+        args: &Bound<'_, PyTuple>,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Self> {
+        let mut arguments = Vec::with_capacity(args.len());
+        for arg in args.iter() {
+            arguments.push(py_object_to_onion_object(py, arg.unbind())?);
+        }
+        if let Some(kwargs) = kwargs {
+            for (key, value) in kwargs.iter() {
+                let key: String = key.extract()?;
+                arguments.push(OnionNamed::new_static(
+                    &OnionObject::String(Arc::new(key)).stabilize(),
+                    &py_object_to_onion_object(py, value.unbind())?,
+                ));
+            }
+        }
+        script::call_lambda_sync(&self.inner, &OnionTuple::new_static_no_ref(&arguments))
+            .map(Self::from_rust)
+            .map_err(runtime_error_to_pyerr)
+    }
+
     // Implement Python's __contains__
     fn __contains__(&self, item: PyObject, py: Python) -> PyResult<bool> {
         let onion_item = py_object_to_onion_object(py, item)?;
@@ -519,6 +691,11 @@ impl PyOnionObject {
         }
     }
 
+    // 递归转换为 Python 原生类型，包装的 Python 对象原样返回
+    fn to_py(&self, py: Python) -> PyResult<PyObject> {
+        onion_object_to_py_native(py, self.inner.weak())
+    }
+
     #[staticmethod]
     fn pair(k: PyObject, v: PyObject, py: Python) -> PyResult<Self> {
         let k = py_object_to_onion_object(py, k)?;
@@ -547,6 +724,34 @@ impl PyOnionObject {
     }
 }
 
+// PyOnionObject 的迭代器，按下标依次调用 at 取出元素
+#[pyclass]
+pub struct PyOnionObjectIterator {
+    inner: OnionStaticObject,
+    index: usize,
+    len: usize,
+}
+
+#[pymethods]
+impl PyOnionObjectIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> PyResult<Option<PyOnionObject>> {
+        if self.index >= self.len {
+            return Ok(None); // 返回 None 时抛出 StopIteration
+        }
+        let element = self
+            .inner
+            .weak()
+            .at(self.index as i64)
+            .map_err(runtime_error_to_pyerr)?;
+        self.index += 1;
+        Ok(Some(PyOnionObject::from_rust(element)))
+    }
+}
+
 pub struct OnionPyObject {
     inner: PyObject,
 }
@@ -612,6 +817,92 @@ pub fn onion_object_to_py(py: Python<'_>, obj: &OnionObject) -> PyResult<PyObjec
     PyOnionObject::from_rust(static_obj).into_py_any(py)
 }
 
+// 递归地将 OnionObject 转换为原生 Python 对象，无法表示的类型保持为 PyOnionObject
+pub fn onion_object_to_py_native(py: Python<'_>, obj: &OnionObject) -> PyResult<PyObject> {
+    let data = obj
+        .with_data(|data| Ok(data.clone()))
+        .map_err(runtime_error_to_pyerr)?;
+    match &data {
+        OnionObject::Integer(i) => (*i).into_py_any(py),
+        OnionObject::Float(f) => (*f).into_py_any(py),
+        OnionObject::String(s) => s.as_str().into_py_any(py),
+        OnionObject::Bytes(b) => PyBytes::new(py, b.as_slice()).into_py_any(py),
+        OnionObject::Boolean(b) => (*b).into_py_any(py),
+        OnionObject::Null => Ok(py.None()),
+        OnionObject::Tuple(tuple) => {
+            let list = PyList::empty(py);
+            for element in tuple.get_elements() {
+                list.append(onion_object_to_py_native(py, element)?)?;
+            }
+            list.into_py_any(py)
+        }
+        OnionObject::Pair(_) => {
+            let key = data.key_of().map_err(runtime_error_to_pyerr)?;
+            let value = data.value_of().map_err(runtime_error_to_pyerr)?;
+            let key = onion_object_to_py_native(py, key.weak())?;
+            let value = onion_object_to_py_native(py, value.weak())?;
+            PyTuple::new(py, [key, value])?.into_py_any(py)
+        }
+        OnionObject::Named(_) => {
+            let key = data.key_of().map_err(runtime_error_to_pyerr)?;
+            let value = data.value_of().map_err(runtime_error_to_pyerr)?;
+            let dict = PyDict::new(py);
+            dict.set_item(
+                onion_object_to_py_native(py, key.weak())?,
+                onion_object_to_py_native(py, value.weak())?,
+            )?;
+            dict.into_py_any(py)
+        }
+        OnionObject::Custom(custom) => match custom.as_any().downcast_ref::<OnionPyObject>() {
+            Some(py_onion) => Ok(py_onion.inner.clone_ref(py)),
+            None => onion_object_to_py(py, &data),
+        },
+        _ => onion_object_to_py(py, &data),
+    }
+}
+
+// 判断 Tuple 是否为非空且仅由 Named 组成的 dict
+fn is_named_dict(elements: &[OnionObject]) -> bool {
+    !elements.is_empty()
+        && elements
+            .iter()
+            .all(|element| matches!(element, OnionObject::Named(_)))
+}
+
+// 将由 Named 组成的元素转换为 Python dict，值通过 dict_value_to_py 递归转换
+fn named_elements_to_py_dict(py: Python<'_>, elements: &[OnionObject]) -> PyResult<PyObject> {
+    let dict = PyDict::new(py);
+    for element in elements {
+        let key = element.key_of().map_err(runtime_error_to_pyerr)?;
+        let value = element.value_of().map_err(runtime_error_to_pyerr)?;
+        dict.set_item(
+            onion_object_to_py_native(py, key.weak())?,
+            dict_value_to_py(py, value.weak())?,
+        )?;
+    }
+    dict.into_py_any(py)
+}
+
+// as_dict 的值转换：嵌套的 Named dict 转为 dict，其他 Tuple 转为 list 并递归处理元素
+fn dict_value_to_py(py: Python<'_>, obj: &OnionObject) -> PyResult<PyObject> {
+    let data = obj
+        .with_data(|data| Ok(data.clone()))
+        .map_err(runtime_error_to_pyerr)?;
+    match &data {
+        OnionObject::Tuple(tuple) if is_named_dict(tuple.get_elements()) => {
+            named_elements_to_py_dict(py, tuple.get_elements())
+        }
+        OnionObject::Tuple(tuple) => {
+            let list = PyList::empty(py);
+            for element in tuple.get_elements() {
+                list.append(dict_value_to_py(py, element)?)?;
+            }
+            list.into_py_any(py)
+        }
+        _ => onion_object_to_py_native(py, &data),
+    }
+}
+
 // Helper function to convert Python objects to OnionObject basic types
 pub fn py_object_to_onion_object(py: Python<'_>, obj: Py<PyAny>) -> PyResult<OnionStaticObject> {
     // 检查输入是否是 PyOnionObject 的实例
@@ -661,28 +952,96 @@ pub fn py_object_to_onion_object(py: Python<'_>, obj: Py<PyAny>) -> PyResult<Oni
         // OnionTuple::new_static_no_ref 需要 OnionStaticObject 的 Vec
         let onion_tuple_elements: Vec<OnionStaticObject> = elements.into_iter().collect();
         Ok(OnionTuple::new_static_no_ref(&onion_tuple_elements))
+    } else if let Ok(dict) = obj.downcast_bound::<PyDict>(py) {
+        // Convert Python dict to a tuple of OnionNamed, keys coerced with str()
+        let mut entries = IndexMap::new();
+        for (key, value) in dict.iter() {
+            let key = key.str()?.to_string();
+            entries.insert(key, py_object_to_onion_object(py, value.into())?);
+        }
+        Ok(build_named_dict(entries))
     } else {
         Ok(OnionObject::Custom(Arc::new(OnionPyObject { inner: obj.into() })).stabilize())
     }
 }
 
+// 在持有 GIL 时提取 context，接受 PyOnionObject 列表或由 Named 组成的 tuple
+fn extract_context(
+    py: Python<'_>,
+    context: Option<PyObject>,
+) -> PyResult<Option<Vec<OnionStaticObject>>> {
+    let Some(ctx) = context else {
+        return Ok(None);
+    };
+    let context_variables: Vec<OnionStaticObject> =
+        if let Ok(tuple) = ctx.extract::<PyRef<PyOnionObject>>(py) {
+            // 也接受由 Named 组成的 tuple，例如 import_py 的返回值
+            tuple
+                .inner
+                .weak()
+                .with_data(|data| match data {
+                    OnionObject::Tuple(tuple) => Ok(tuple
+                        .get_elements()
+                        .iter()
+                        .map(|element| element.stabilize())
+                        .collect()),
+                    _ => Err(RuntimeError::InvalidType(
+                        "context must be a list or a tuple of named values"
+                            .to_string()
+                            .into(),
+                    )),
+                })
+                .map_err(runtime_error_to_pyerr)?
+        } else {
+            let ctx_list: Vec<PyOnionObject> = ctx.extract(py)?;
+            ctx_list.into_iter().map(|obj| obj.inner).collect()
+        };
+    Ok(Some(context_variables))
+}
+
+/// Convert the `extra_stdlib` dict into entries merged under `stdlib`
+fn extract_extra_stdlib(
+    py: Python<'_>,
+    extra_stdlib: Option<Bound<'_, PyDict>>,
+) -> PyResult<Option<IndexMap<String, OnionStaticObject>>> {
+    let Some(dict) = extra_stdlib else {
+        return Ok(None);
+    };
+    let mut entries = IndexMap::new();
+    for (key, value) in dict.iter() {
+        let name: String = key
+            .extract()
+            .map_err(|_| PyTypeError::new_err("extra_stdlib keys must be strings"))?;
+        entries.insert(name, py_object_to_onion_object(py, value.unbind())?);
+    }
+    Ok(Some(entries))
+}
+
 /// An asynchronous Python function implemented in Rust.
 #[pyfunction]
+#[pyo3(signature = (
+    code,
+    work_dir = None,
+    context = None,
+    timeout_ms = None,
+    max_steps = None,
+    extra_stdlib = None
+))]
 fn eval<'pya>(
     py: Python<'pya>,
     code: String,
     work_dir: Option<String>,
     context: Option<PyObject>,
+    timeout_ms: Option<u64>,
+    max_steps: Option<u64>,
+    extra_stdlib: Option<Bound<'pya, PyDict>>,
 ) -> PyResult<Bound<'pya, PyAny>> {
     // Extract context to a serializable form before entering async block
-    let context_serialized = if let Some(ctx) = context {
-        // Extract the context list in the current thread (with GIL)
-        let ctx_list: Vec<PyOnionObject> = ctx.extract(py)?;
-        let context_variables: Vec<OnionStaticObject> =
-            ctx_list.into_iter().map(|obj| obj.inner).collect();
-        Some(context_variables)
-    } else {
-        None
+    let context_serialized = extract_context(py, context)?;
+    let extra_stdlib = extract_extra_stdlib(py, extra_stdlib)?;
+    let limits = script::ExecutionLimits {
+        timeout: timeout_ms.map(std::time::Duration::from_millis),
+        max_steps,
     };
 
     future_into_py(py, async move {
@@ -698,19 +1057,125 @@ fn eval<'pya>(
         };
         let context_variables_ref: Option<Vec<&OnionStaticObject>> =
             context_serialized.as_ref().map(|v| v.iter().collect());
-        let result = match script::eval(&code, &mut dir_stack, context_variables_ref).await {
-            Ok(value) => value,
-            Err(err) => {
-                return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
-                    "Failed to evaluate script: {}",
-                    err
-                )));
-            }
-        };
+        let result = script::eval(
+            &code,
+            &mut dir_stack,
+            context_variables_ref,
+            extra_stdlib.as_ref(),
+            &limits,
+        )
+        .await
+        .map_err(execution_error_to_pyerr)?;
         Python::with_gil(|py| PyOnionObject::from_rust(result).into_py_any(py))
     })
 }
 
+/// Evaluate a script on the calling thread without an asyncio event loop.
+#[pyfunction]
+#[pyo3(signature = (
+    code,
+    work_dir = None,
+    context = None,
+    timeout_ms = None,
+    max_steps = None,
+    extra_stdlib = None
+))]
+fn eval_sync(
+    py: Python<'_>,
+    code: String,
+    work_dir: Option<String>,
+    context: Option<PyObject>,
+    timeout_ms: Option<u64>,
+    max_steps: Option<u64>,
+    extra_stdlib: Option<Bound<'_, PyDict>>,
+) -> PyResult<PyOnionObject> {
+    let context_serialized = extract_context(py, context)?;
+    let extra_stdlib = extract_extra_stdlib(py, extra_stdlib)?;
+    let limits = script::ExecutionLimits {
+        timeout: timeout_ms.map(std::time::Duration::from_millis),
+        max_steps,
+    };
+
+    // 执行期间释放 GIL，Python 回调会按需重新获取
+    py.allow_threads(move || {
+        let work_dir_pathbuf = work_dir.map(std::path::PathBuf::from);
+        let mut dir_stack = DirectoryStack::new(work_dir_pathbuf.as_deref()).map_err(|err| {
+            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                "Failed to create directory stack: {}",
+                err
+            ))
+        })?;
+        let context_variables_ref: Option<Vec<&OnionStaticObject>> =
+            context_serialized.as_ref().map(|v| v.iter().collect());
+        script::eval_sync(
+            &code,
+            &mut dir_stack,
+            context_variables_ref,
+            extra_stdlib.as_ref(),
+            &limits,
+        )
+        .map_err(execution_error_to_pyerr)
+    })
+    .map(PyOnionObject::from_rust)
+}
+
+/// Compile a script once so it can be run many times with different contexts.
+#[pyfunction]
+#[pyo3(signature = (code, work_dir = None))]
+fn compile(code: String, work_dir: Option<String>) -> PyResult<PyOnionProgram> {
+    let work_dir_pathbuf = work_dir.map(std::path::PathBuf::from);
+    let mut dir_stack = DirectoryStack::new(work_dir_pathbuf.as_deref()).map_err(|err| {
+        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+            "Failed to create directory stack: {}",
+            err
+        ))
+    })?;
+    let package = script::compile(&code, &mut dir_stack).map_err(|err| {
+        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+            "Failed to compile script: {}",
+            err
+        ))
+    })?;
+    Ok(PyOnionProgram {
+        package: Arc::new(package),
+    })
+}
+
+// 预编译的脚本，字节码通过 Arc 共享，可反复执行
+#[pyclass]
+#[derive(Clone)]
+pub struct PyOnionProgram {
+    package: Arc<VMInstructionPackage>,
+}
+
+#[pymethods]
+impl PyOnionProgram {
+    // 与 eval 相同，返回一个 awaitable，结果为 (success, value) 的 Pair
+    #[pyo3(signature = (context = None))]
+    fn run<'pya>(
+        &self,
+        py: Python<'pya>,
+        context: Option<PyObject>,
+    ) -> PyResult<Bound<'pya, PyAny>> {
+        let context_serialized = extract_context(py, context)?;
+        let package = self.package.clone();
+
+        future_into_py(py, async move {
+            let context_variables_ref: Option<Vec<&OnionStaticObject>> =
+                context_serialized.as_ref().map(|v| v.iter().collect());
+            let result = script::execute_bytecode_package(
+                &package,
+                context_variables_ref,
+                None,
+                &script::ExecutionLimits::default(),
+            )
+            .await
+            .map_err(execution_error_to_pyerr)?;
+            Python::with_gil(|py| PyOnionObject::from_rust(result).into_py_any(py))
+        })
+    }
+}
+
 #[pyfunction]
 fn wrap_py_function<'py>(
     params: PyObject,
@@ -763,11 +1228,95 @@ fn wrap_py_coroutine<'py>(
     )))
 }
 
+/// Import callables from a Python module as a named tuple usable as `eval` context.
+#[pyfunction]
+fn import_py(module_name: String, names: Vec<String>, py: Python<'_>) -> PyResult<PyOnionObject> {
+    let module = py.import(module_name.as_str())?;
+    let inspect = py.import("inspect")?;
+    let mut exports = IndexMap::new();
+    for name in names {
+        let function = module.getattr(name.as_str())?;
+        if !function.is_callable() {
+            return Err(PyTypeError::new_err(format!(
+                "{}.{} is not callable",
+                module_name, name
+            )));
+        }
+        // 根据 Python 的函数签名构建 Onion 参数列表
+        let signature = match inspect.call_method1("signature", (&function,)) {
+            Ok(signature) => signature,
+            // builtins.max 等 C 函数没有可检查的签名，退化为接收一个参数元组
+            Err(e) if e.is_instance_of::<PyValueError>(py) => {
+                let mut params = IndexMap::new();
+                params.insert(
+                    "args".to_string(),
+                    OnionObject::Undefined(Some(
+                        "Tuple of positional arguments".to_string().into(),
+                    ))
+                    .stabilize(),
+                );
+                exports.insert(
+                    name.clone(),
+                    pycallable::wrap_py_variadic_function(
+                        &build_named_dict(params),
+                        format!("{}::{}", module_name, name),
+                        function.unbind(),
+                    ),
+                );
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        let mut params = IndexMap::new();
+        for parameter in signature
+            .getattr("parameters")?
+            .call_method0("values")?
+            .try_iter()?
+        {
+            let parameter = parameter?;
+            let kind: String = parameter.getattr("kind")?.getattr("name")?.extract()?;
+            if kind != "POSITIONAL_ONLY" && kind != "POSITIONAL_OR_KEYWORD" {
+                continue;
+            }
+            let param_name: String = parameter.getattr("name")?.extract()?;
+            let default = parameter.getattr("default")?;
+            let default = if default.is(&signature.getattr("empty")?) {
+                OnionObject::Undefined(Some(format!("Argument '{}'", param_name).into()))
+                    .stabilize()
+            } else {
+                py_object_to_onion_object(py, default.unbind())?
+            };
+            params.insert(param_name, default);
+        }
+        exports.insert(
+            name.clone(),
+            pycallable::wrap_py_positional_function(
+                &build_named_dict(params),
+                format!("{}::{}", module_name, name),
+                function.unbind(),
+            ),
+        );
+    }
+    Ok(PyOnionObject::from_rust(build_named_dict(exports)))
+}
+
 #[pymodule(name = "onion")]
 fn onion_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(eval, m)?)?;
+    m.add_function(wrap_pyfunction!(eval_sync, m)?)?;
+    m.add_function(wrap_pyfunction!(compile, m)?)?;
     m.add_function(wrap_pyfunction!(wrap_py_function, m)?)?;
     m.add_function(wrap_pyfunction!(wrap_py_coroutine, m)?)?;
+    m.add_function(wrap_pyfunction!(import_py, m)?)?;
     m.add_class::<PyOnionObject>()?; // 注册新的 Python 类
+    m.add_class::<PyOnionObjectIterator>()?;
+    m.add_class::<PyOnionProgram>()?;
+    m.add("OnionError", m.py().get_type::<OnionError>())?;
+    m.add("OnionPending", m.py().get_type::<OnionPending>())?;
+    m.add("OnionTimeout", m.py().get_type::<OnionTimeout>())?;
+    m.add(
+        "OnionStepLimitExceeded",
+        m.py().get_type::<OnionStepLimitExceeded>(),
+    )?;
     Ok(())
 }
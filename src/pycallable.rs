@@ -5,15 +5,16 @@ use onion_vm::{
     onion_tuple,
     types::{
         lambda::definition::{LambdaBody, OnionLambdaDefinition},
-        object::{OnionObjectCell, OnionStaticObject},
+        object::{OnionObject, OnionObjectCell, OnionStaticObject},
         tuple::OnionTuple,
     },
     unwrap_step_result, GC,
 };
-use pyo3::{PyObject, PyResult, Python};
+use pyo3::{types::PyTuple, PyObject, PyResult, Python};
 
 use crate::{
-    py_object_to_onion_object, pyerr_to_runtime_error, script::stdlib::dummy_waker, PyOnionObject,
+    onion_object_to_py_native, py_object_to_onion_object, pyerr_to_runtime_error,
+    script::stdlib::dummy_waker, PyOnionObject,
 };
 
 use std::{
@@ -28,6 +29,10 @@ pub struct PyFunctionGenerator {
     argument: OnionStaticObject,
     self_object: Option<OnionStaticObject>,
     function: Arc<PyObject>,
+    // 为 true 时将参数展开为原生 Python 位置参数，而不是 (self_object, argument)
+    positional: bool,
+    // 为 true 时唯一的参数是一个元组，其元素再逐个作为位置参数传入
+    variadic: bool,
 }
 
 impl Runnable for PyFunctionGenerator {
@@ -41,7 +46,46 @@ impl Runnable for PyFunctionGenerator {
                 .map(|obj| PyOnionObject::from_rust(obj));
 
             // Call the Python function with the provided arguments
-            let result = function.call1(py, (self_object, argument));
+            let result = if self.positional {
+                let args = unwrap_step_result!(self.argument.weak().with_data(|data| match data {
+                    OnionObject::Tuple(tuple) => tuple
+                        .get_elements()
+                        .iter()
+                        .map(|element| element.value_of())
+                        .collect::<Result<Vec<_>, _>>(),
+                    _ => Err(RuntimeError::InvalidType(
+                        "Python function expects a tuple of arguments"
+                            .to_string()
+                            .into(),
+                    )),
+                }));
+                let args = if self.variadic && !args.is_empty() {
+                    unwrap_step_result!(args[0].weak().with_data(|data| Ok(match data {
+                        OnionObject::Tuple(tuple) => tuple
+                            .get_elements()
+                            .iter()
+                            .map(|element| element.stabilize())
+                            .collect(),
+                        _ => vec![args[0].clone()],
+                    })))
+                } else {
+                    args
+                };
+                let mut py_args = Vec::with_capacity(args.len());
+                for arg in &args {
+                    py_args.push(unwrap_step_result!(onion_object_to_py_native(
+                        py,
+                        arg.weak()
+                    )
+                    .map_err(|e| pyerr_to_runtime_error(e, py))));
+                }
+                let py_args = unwrap_step_result!(
+                    PyTuple::new(py, py_args).map_err(|e| pyerr_to_runtime_error(e, py))
+                );
+                function.call1(py, py_args)
+            } else {
+                function.call1(py, (self_object, argument))
+            };
 
             // 检查result是否为PyOnionObject
             if !result.is_ok() {
@@ -84,6 +128,8 @@ impl Runnable for PyFunctionGenerator {
             argument: self.argument.clone(),
             self_object: self.self_object.clone(),
             function: self.function.clone(),
+            positional: self.positional,
+            variadic: self.variadic,
         })
     }
 
@@ -108,6 +154,8 @@ pub fn wrap_py_function(
             argument: onion_tuple!(),
             self_object: self_object.cloned(),
             function: Arc::new(function),
+            positional: false,
+            variadic: false,
         })),
         capture,
         self_object,
@@ -115,6 +163,50 @@ pub fn wrap_py_function(
     )
 }
 
+/// Wrap a plain Python callable, spreading the bound arguments as native
+/// Python positional arguments instead of passing `(self_object, argument)`.
+pub fn wrap_py_positional_function(
+    params: &OnionStaticObject,
+    signature: String,
+    function: PyObject,
+) -> OnionStaticObject {
+    OnionLambdaDefinition::new_static(
+        params,
+        LambdaBody::NativeFunction(Box::new(PyFunctionGenerator {
+            argument: onion_tuple!(),
+            self_object: None,
+            function: Arc::new(function),
+            positional: true,
+            variadic: false,
+        })),
+        None,
+        None,
+        signature,
+    )
+}
+
+/// Like `wrap_py_positional_function`, but the single bound argument is a
+/// tuple whose elements are spread as the Python positional arguments.
+pub fn wrap_py_variadic_function(
+    params: &OnionStaticObject,
+    signature: String,
+    function: PyObject,
+) -> OnionStaticObject {
+    OnionLambdaDefinition::new_static(
+        params,
+        LambdaBody::NativeFunction(Box::new(PyFunctionGenerator {
+            argument: onion_tuple!(),
+            self_object: None,
+            function: Arc::new(function),
+            positional: true,
+            variadic: true,
+        })),
+        None,
+        None,
+        signature,
+    )
+}
+
 pub struct PyCoroutineGenerator {
     // 存储原始的 Python 协程对象
     python_coroutine: PyObject,
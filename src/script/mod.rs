@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use indexmap::IndexMap;
 use onion_frontend::{compile::build_code, utils::cycle_detector};
 use onion_vm::{
     lambda::{
@@ -25,16 +26,140 @@ pub mod stdlib;
 pub use onion_frontend;
 pub use onion_vm;
 
+use std::fmt;
+use std::time::Instant;
 use tokio::time::{sleep, Duration};
 
+/// Budgets that stop a script run early
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ExecutionLimits {
+    /// Wall-clock time the scheduler may run for
+    pub timeout: Option<Duration>,
+    /// Number of scheduler steps the script may take
+    pub max_steps: Option<u64>,
+}
+
+/// Why a script run stopped without returning a value
+#[derive(Debug)]
+pub enum ExecutionError {
+    /// Compilation or setup failed
+    Failed(String),
+    /// The script raised an error while running
+    Runtime(RuntimeError),
+    /// The run exceeded `ExecutionLimits::timeout`
+    Timeout(Duration),
+    /// The run exceeded `ExecutionLimits::max_steps`
+    StepLimitExceeded(u64),
+}
+
+impl fmt::Display for ExecutionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecutionError::Failed(message) => write!(f, "{}", message),
+            ExecutionError::Runtime(error) => write!(f, "Execution error: {}", error),
+            ExecutionError::Timeout(timeout) => {
+                write!(f, "Execution timed out after {} ms", timeout.as_millis())
+            }
+            ExecutionError::StepLimitExceeded(max_steps) => {
+                write!(f, "Execution exceeded the limit of {} steps", max_steps)
+            }
+        }
+    }
+}
+
+impl From<String> for ExecutionError {
+    fn from(message: String) -> Self {
+        ExecutionError::Failed(message)
+    }
+}
+
+/// Tracks the time and steps used by a single run against its limits
+struct Budget {
+    started: Instant,
+    steps: u64,
+    limits: ExecutionLimits,
+}
+
+impl Budget {
+    fn start(limits: &ExecutionLimits) -> Self {
+        Budget {
+            started: Instant::now(),
+            steps: 0,
+            limits: *limits,
+        }
+    }
+
+    /// Account for one more scheduler step, failing once a limit is exceeded
+    fn step(&mut self) -> Result<(), ExecutionError> {
+        self.steps += 1;
+        if let Some(max_steps) = self.limits.max_steps {
+            if self.steps > max_steps {
+                return Err(ExecutionError::StepLimitExceeded(max_steps));
+            }
+        }
+        match self.limits.timeout {
+            Some(timeout) if self.started.elapsed() >= timeout => {
+                Err(ExecutionError::Timeout(timeout))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
 pub async fn eval(
     code: &str,
     dir_stack: &mut onion_frontend::dir_stack::DirectoryStack,
     context: Option<Vec<&OnionStaticObject>>,
-) -> Result<OnionStaticObject, String> {
+    extra_stdlib: Option<&IndexMap<String, OnionStaticObject>>,
+    limits: &ExecutionLimits,
+) -> Result<OnionStaticObject, ExecutionError> {
     // Execute the code and return the result
     let mut cycle_detector = cycle_detector::CycleDetector::new();
-    execute_code(code, &mut cycle_detector, dir_stack, context).await
+    execute_code(
+        code,
+        &mut cycle_detector,
+        dir_stack,
+        context,
+        extra_stdlib,
+        limits,
+    )
+    .await
+}
+
+/// Synchronous counterpart of `eval` that steps the scheduler on the calling thread.
+///
+/// Scripts awaiting Python coroutines fail with a pending error, as there is no
+/// event loop to drive them.
+pub fn eval_sync(
+    code: &str,
+    dir_stack: &mut onion_frontend::dir_stack::DirectoryStack,
+    context: Option<Vec<&OnionStaticObject>>,
+    extra_stdlib: Option<&IndexMap<String, OnionStaticObject>>,
+    limits: &ExecutionLimits,
+) -> Result<OnionStaticObject, ExecutionError> {
+    let vm_instructions_package = compile(code, dir_stack)?;
+    let scheduler = build_scheduler(&vm_instructions_package, context, extra_stdlib)?;
+    run_scheduler_sync(scheduler, limits)
+}
+
+/// Compile and validate a script into bytecode that can be executed repeatedly
+pub fn compile(
+    code: &str,
+    dir_stack: &mut onion_frontend::dir_stack::DirectoryStack,
+) -> Result<VMInstructionPackage, String> {
+    let mut cycle_detector = cycle_detector::CycleDetector::new();
+    let ir_package = build_code(code, &mut cycle_detector, dir_stack)
+        .map_err(|e| format!("Compilation failed: {}", e))?;
+
+    let mut translator = IRTranslator::new(&ir_package);
+    translator
+        .translate()
+        .map_err(|e| format!("IR translation failed: {:?}", e))?;
+
+    let vm_instructions_package = translator.get_result();
+    VMInstructionPackage::validate(&vm_instructions_package)
+        .map_err(|e| format!("Invalid VM instruction package: {}", e))?;
+    Ok(vm_instructions_package)
 }
 
 async fn execute_code(
@@ -42,41 +167,50 @@ async fn execute_code(
     cycle_detector: &mut cycle_detector::CycleDetector<String>,
     dir_stack: &mut onion_frontend::dir_stack::DirectoryStack,
     context: Option<Vec<&OnionStaticObject>>,
-) -> Result<OnionStaticObject, String> {
+    extra_stdlib: Option<&IndexMap<String, OnionStaticObject>>,
+    limits: &ExecutionLimits,
+) -> Result<OnionStaticObject, ExecutionError> {
     let ir_package = build_code(code, cycle_detector, dir_stack)
         .map_err(|e| format!("Compilation failed: {}", e))?;
 
-    execute_ir_package(&ir_package, context).await
+    execute_ir_package(&ir_package, context, extra_stdlib, limits).await
 }
 
 async fn execute_ir_package(
     ir_package: &IRPackage,
     context: Option<Vec<&OnionStaticObject>>,
-) -> Result<OnionStaticObject, String> {
+    extra_stdlib: Option<&IndexMap<String, OnionStaticObject>>,
+    limits: &ExecutionLimits,
+) -> Result<OnionStaticObject, ExecutionError> {
     let mut translator = IRTranslator::new(ir_package);
     translator
         .translate()
         .map_err(|e| format!("IR translation failed: {:?}", e))?;
 
     let vm_instructions_package = translator.get_result();
-    execute_bytecode_package(&vm_instructions_package, context).await
+    execute_bytecode_package(&vm_instructions_package, context, extra_stdlib, limits).await
 }
 
-// Modify execute_bytecode_package to be async
-pub async fn execute_bytecode_package(
+/// Validate the bytecode and wrap it, with stdlib and context bound, in a scheduler
+fn build_scheduler(
     vm_instructions_package: &VMInstructionPackage,
     context: Option<Vec<&OnionStaticObject>>,
-) -> Result<OnionStaticObject, String> {
-    let mut gc = GC::new_with_memory_threshold(1024 * 1024); // 1 MB threshold
-
+    extra_stdlib: Option<&IndexMap<String, OnionStaticObject>>,
+) -> Result<Box<dyn Runnable>, String> {
     match VMInstructionPackage::validate(vm_instructions_package) {
         Err(e) => return Err(format!("Invalid VM instruction package: {}", e)),
         Ok(_) => {}
     }
-    // Create standard library object
+    // Create standard library object, with any host-provided entries merged in
+    let stdlib_module = match extra_stdlib {
+        Some(extra) => {
+            stdlib::build_module_with(extra).map_err(|e| format!("Invalid extra stdlib: {}", e))?
+        }
+        None => stdlib::build_module(),
+    };
     let stdlib_pair = OnionNamed::new_static(
         &OnionObject::String(Arc::new("stdlib".to_string())).consume_and_stabilize(),
-        &stdlib::build_module(),
+        &stdlib_module,
     );
 
     // Create Lambda definition
@@ -103,15 +237,29 @@ pub async fn execute_bytecode_package(
 
     let args = OnionTuple::new_static(vec![]);
 
-    // 初始化调度器和GC
-    let mut scheduler: Box<dyn Runnable> = Box::new(
+    // 初始化调度器
+    Ok(Box::new(
         OnionLambdaRunnableLauncher::new_static(&lambda, &args, |r| {
             Ok(Box::new(Scheduler::new(vec![r])))
         })
         .map_err(|e| format!("Failed to create runnable Lambda: {:?}", e))?,
-    );
+    ))
+}
+
+// Modify execute_bytecode_package to be async
+pub async fn execute_bytecode_package(
+    vm_instructions_package: &VMInstructionPackage,
+    context: Option<Vec<&OnionStaticObject>>,
+    extra_stdlib: Option<&IndexMap<String, OnionStaticObject>>,
+    limits: &ExecutionLimits,
+) -> Result<OnionStaticObject, ExecutionError> {
+    let mut gc = GC::new_with_memory_threshold(1024 * 1024); // 1 MB threshold
+    let mut scheduler = build_scheduler(vm_instructions_package, context, extra_stdlib)?;
+    let mut budget = Budget::start(limits);
     // Execute code
     loop {
+        // Checked before every step so scripts that never yield still stop
+        budget.step()?;
         match scheduler.step(&mut gc) {
             StepResult::Continue => {
                 // Continue to next step
@@ -119,21 +267,27 @@ pub async fn execute_bytecode_package(
                 sleep(Duration::from_secs(0)).await;
             }
             StepResult::SetSelfObject(_) => {
-                return Err("Invalid operation: SetSelfObject is not supported".to_string());
+                return Err("Invalid operation: SetSelfObject is not supported"
+                    .to_string()
+                    .into());
             }
             StepResult::SpawnRunnable(_) => {
-                return Err("Invalid operation: SpawnRunnable is not supported".to_string());
+                return Err("Invalid operation: SpawnRunnable is not supported"
+                    .to_string()
+                    .into());
             }
-            StepResult::Error(ref error) => {
+            StepResult::Error(error) => {
                 if let RuntimeError::Pending = error {
                     // If the error is pending, we can continue
                     sleep(Duration::from_secs(0)).await;
                     continue;
                 }
-                return Err(format!("Execution error: {}", error));
+                return Err(ExecutionError::Runtime(error));
             }
             StepResult::NewRunnable(_) => {
-                return Err("Invalid operation: NewRunnable is not supported".to_string());
+                return Err("Invalid operation: NewRunnable is not supported"
+                    .to_string()
+                    .into());
             }
             StepResult::ReplaceRunnable(ref r) => {
                 scheduler = r.copy();
@@ -146,3 +300,60 @@ pub async fn execute_bytecode_package(
         }
     }
 }
+
+/// Run `lambda` with `args` on a fresh scheduler, blocking until it returns.
+///
+/// Python coroutines cannot make progress without the event loop, so a pending
+/// step is reported as `RuntimeError::Pending` instead of being waited on.
+pub fn call_lambda_sync(
+    lambda: &OnionStaticObject,
+    args: &OnionStaticObject,
+) -> Result<OnionStaticObject, RuntimeError> {
+    let scheduler: Box<dyn Runnable> = Box::new(OnionLambdaRunnableLauncher::new_static(
+        lambda,
+        args,
+        |r| Ok(Box::new(Scheduler::new(vec![r]))),
+    )?);
+    run_scheduler_sync(scheduler, &ExecutionLimits::default()).map_err(|e| match e {
+        ExecutionError::Runtime(error) => error,
+        other => RuntimeError::DetailedError(other.to_string().into()),
+    })
+}
+
+/// Step `scheduler` on the calling thread until it returns or fails
+fn run_scheduler_sync(
+    mut scheduler: Box<dyn Runnable>,
+    limits: &ExecutionLimits,
+) -> Result<OnionStaticObject, ExecutionError> {
+    let mut gc = GC::new_with_memory_threshold(1024 * 1024); // 1 MB threshold
+    let mut budget = Budget::start(limits);
+
+    loop {
+        budget.step()?;
+        match scheduler.step(&mut gc) {
+            StepResult::Continue => {}
+            StepResult::ReplaceRunnable(ref r) => {
+                scheduler = r.copy();
+            }
+            StepResult::Return(ref result) => {
+                return Ok(result.as_ref().clone());
+            }
+            StepResult::Error(error) => return Err(ExecutionError::Runtime(error)),
+            StepResult::SetSelfObject(_) => {
+                return Err("Invalid operation: SetSelfObject is not supported"
+                    .to_string()
+                    .into());
+            }
+            StepResult::SpawnRunnable(_) => {
+                return Err("Invalid operation: SpawnRunnable is not supported"
+                    .to_string()
+                    .into());
+            }
+            StepResult::NewRunnable(_) => {
+                return Err("Invalid operation: NewRunnable is not supported"
+                    .to_string()
+                    .into());
+            }
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use std::cmp::Ordering;
+
+use indexmap::IndexMap;
+use num_bigint::BigInt;
+use onion_vm::{
+    lambda::runnable::RuntimeError,
+    types::object::{OnionObject, OnionObjectCell, OnionStaticObject},
+    GC,
+};
+
+use super::{build_named_dict, get_attr_direct, wrap_native_function};
+
+/// Parse an integer or decimal string into a BigInt
+fn to_bigint(obj: &OnionObject, func_name: &str) -> Result<BigInt, RuntimeError> {
+    match obj {
+        OnionObject::Integer(n) => Ok(BigInt::from(*n)),
+        OnionObject::String(s) => s.trim().parse::<BigInt>().map_err(|e| {
+            RuntimeError::InvalidOperation(
+                format!("{} cannot parse '{}' as integer: {}", func_name, s, e).into(),
+            )
+        }),
+        _ => Err(RuntimeError::InvalidOperation(
+            format!("{} requires integer or decimal string arguments", func_name).into(),
+        )),
+    }
+}
+
+/// Apply a binary operation to the `a` and `b` arguments
+fn binary_op<F>(
+    argument: &OnionStaticObject,
+    func_name: &str,
+    op: F,
+) -> Result<OnionStaticObject, RuntimeError>
+where
+    F: Fn(BigInt, BigInt) -> Result<OnionStaticObject, RuntimeError>,
+{
+    argument.weak().with_data(|data| {
+        let a = get_attr_direct(data, "a".to_string())?;
+        let b = get_attr_direct(data, "b".to_string())?;
+        let a = a.weak().with_data(|a_data| to_bigint(a_data, func_name))?;
+        let b = b.weak().with_data(|b_data| to_bigint(b_data, func_name))?;
+        op(a, b)
+    })
+}
+
+fn bigint_result(value: BigInt) -> Result<OnionStaticObject, RuntimeError> {
+    Ok(OnionObject::String(value.to_string().into()).stabilize())
+}
+
+fn check_divisor(divisor: &BigInt) -> Result<(), RuntimeError> {
+    if *divisor == BigInt::from(0) {
+        Err(RuntimeError::InvalidOperation(
+            "Division by zero".to_string().into(),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+fn add(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    binary_op(argument, "add", |a, b| bigint_result(a + b))
+}
+
+fn sub(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    binary_op(argument, "sub", |a, b| bigint_result(a - b))
+}
+
+fn mul(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    binary_op(argument, "mul", |a, b| bigint_result(a * b))
+}
+
+/// Integer division truncating toward zero
+fn div(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    binary_op(argument, "div", |a, b| {
+        check_divisor(&b)?;
+        bigint_result(a / b)
+    })
+}
+
+/// Remainder with the sign of the dividend
+fn modulo(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    binary_op(argument, "mod", |a, b| {
+        check_divisor(&b)?;
+        bigint_result(a % b)
+    })
+}
+
+/// Compare two big integers, returning -1, 0 or 1
+fn compare(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    binary_op(argument, "compare", |a, b| {
+        let ordering = match a.cmp(&b) {
+            Ordering::Less => -1,
+            Ordering::Equal => 0,
+            Ordering::Greater => 1,
+        };
+        Ok(OnionObject::Integer(ordering).stabilize())
+    })
+}
+
+fn binary_params() -> OnionStaticObject {
+    let mut params = IndexMap::new();
+    params.insert(
+        "a".to_string(),
+        OnionObject::Undefined(Some("Left operand as decimal string".to_string().into()))
+            .stabilize(),
+    );
+    params.insert(
+        "b".to_string(),
+        OnionObject::Undefined(Some("Right operand as decimal string".to_string().into()))
+            .stabilize(),
+    );
+    build_named_dict(params)
+}
+
+/// 构建大整数模块，所有结果以十进制字符串返回
+pub fn build_module() -> OnionStaticObject {
+    let mut module = IndexMap::new();
+
+    module.insert(
+        "add".to_string(),
+        wrap_native_function(
+            &binary_params(),
+            None,
+            None,
+            "bigint::add".to_string(),
+            &add,
+        ),
+    );
+    module.insert(
+        "sub".to_string(),
+        wrap_native_function(
+            &binary_params(),
+            None,
+            None,
+            "bigint::sub".to_string(),
+            &sub,
+        ),
+    );
+    module.insert(
+        "mul".to_string(),
+        wrap_native_function(
+            &binary_params(),
+            None,
+            None,
+            "bigint::mul".to_string(),
+            &mul,
+        ),
+    );
+    module.insert(
+        "div".to_string(),
+        wrap_native_function(
+            &binary_params(),
+            None,
+            None,
+            "bigint::div".to_string(),
+            &div,
+        ),
+    );
+    module.insert(
+        "mod".to_string(),
+        wrap_native_function(
+            &binary_params(),
+            None,
+            None,
+            "bigint::mod".to_string(),
+            &modulo,
+        ),
+    );
+    module.insert(
+        "compare".to_string(),
+        wrap_native_function(
+            &binary_params(),
+            None,
+            None,
+            "bigint::compare".to_string(),
+            &compare,
+        ),
+    );
+
+    build_named_dict(module)
+}
@@ -5,7 +5,7 @@ use onion_vm::{
     GC,
 };
 
-use super::{build_named_dict, get_attr_direct, wrap_native_function};
+use super::{build_named_dict, get_attr_direct, wrap_native_function, MAX_GENERATED_LEN};
 
 /// Get the length of bytes
 fn length(
@@ -535,6 +535,419 @@ fn to_integers(
     })
 }
 
+/// Concatenate a tuple of byte arrays
+fn concat_all(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let parts = get_attr_direct(data, "parts".to_string())?;
+        parts.weak().with_data(|parts_data| match parts_data {
+            OnionObject::Tuple(tuple) => {
+                let elements = tuple.get_elements();
+                let mut chunks = Vec::with_capacity(elements.len());
+                for (index, element) in elements.iter().enumerate() {
+                    match element {
+                        OnionObject::Bytes(b) => chunks.push(b.clone()),
+                        _ => {
+                            return Err(RuntimeError::InvalidOperation(
+                                format!("concat_all element {} is not bytes", index).into(),
+                            ))
+                        }
+                    }
+                }
+                let total: usize = chunks.iter().map(|chunk| chunk.len()).sum();
+                let mut result = Vec::with_capacity(total);
+                for chunk in chunks {
+                    result.extend_from_slice(&chunk);
+                }
+                Ok(OnionObject::Bytes(result.into()).stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "concat_all requires a tuple of bytes".to_string().into(),
+            )),
+        })
+    })
+}
+
+/// Number of leading bytes shared by two byte arrays
+fn common_prefix_len(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let a = get_attr_direct(data, "a".to_string())?;
+        let b = get_attr_direct(data, "b".to_string())?;
+
+        a.weak().with_data(|a_data| {
+            b.weak().with_data(|b_data| match (a_data, b_data) {
+                (OnionObject::Bytes(a), OnionObject::Bytes(b)) => {
+                    let len = a.iter().zip(b.iter()).take_while(|(x, y)| x == y).count();
+                    Ok(OnionObject::Integer(len as i64).stabilize())
+                }
+                _ => Err(RuntimeError::InvalidOperation(
+                    "common_prefix_len requires bytes arguments"
+                        .to_string()
+                        .into(),
+                )),
+            })
+        })
+    })
+}
+
+/// Convert bytes to string, replacing invalid UTF-8 sequences with U+FFFD
+fn to_string_lossy(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let bytes = get_attr_direct(data, "bytes".to_string())?;
+        bytes.weak().with_data(|bytes_data| match bytes_data {
+            OnionObject::Bytes(b) => {
+                let s = String::from_utf8_lossy(b).into_owned();
+                Ok(OnionObject::String(s.into()).stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "to_string_lossy requires bytes".to_string().into(),
+            )),
+        })
+    })
+}
+
+/// Append pad_byte until the length is a multiple of block_size
+fn pad_to_multiple(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let bytes = get_attr_direct(data, "bytes".to_string())?;
+        let block_size = get_attr_direct(data, "block_size".to_string())?;
+        let pad_byte = get_attr_direct(data, "pad_byte".to_string())?;
+
+        match (bytes.weak(), block_size.weak(), pad_byte.weak()) {
+            (OnionObject::Bytes(b), OnionObject::Integer(block), OnionObject::Integer(pad)) => {
+                if *block <= 0 {
+                    return Err(RuntimeError::InvalidOperation(
+                        "pad_to_multiple requires a positive block_size"
+                            .to_string()
+                            .into(),
+                    ));
+                }
+                let pad = u8::try_from(*pad).map_err(|_| {
+                    RuntimeError::InvalidOperation(
+                        format!("pad_to_multiple pad_byte {} is out of range 0-255", pad).into(),
+                    )
+                })?;
+                let block = *block as usize;
+                let pad_count = (block - b.len() % block) % block;
+                let padded_len = b
+                    .len()
+                    .checked_add(pad_count)
+                    .filter(|len| *len <= MAX_GENERATED_LEN)
+                    .ok_or_else(|| {
+                        RuntimeError::InvalidOperation(
+                            "Padded bytes would be too large".to_string().into(),
+                        )
+                    })?;
+                let mut result = b.as_ref().clone();
+                result.resize(padded_len, pad);
+                Ok(OnionObject::Bytes(result.into()).stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "pad_to_multiple requires bytes and integer arguments"
+                    .to_string()
+                    .into(),
+            )),
+        }
+    })
+}
+
+/// Find the start offsets of all non-overlapping occurrences of a pattern
+fn find_all(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    use onion_vm::types::tuple::OnionTuple;
+
+    argument.weak().with_data(|data| {
+        let bytes = get_attr_direct(data, "bytes".to_string())?;
+        let pattern = get_attr_direct(data, "pattern".to_string())?;
+
+        bytes.weak().with_data(|bytes_data| {
+            pattern
+                .weak()
+                .with_data(|pattern_data| match (bytes_data, pattern_data) {
+                    (OnionObject::Bytes(b), OnionObject::Bytes(pat)) => {
+                        if pat.is_empty() {
+                            return Err(RuntimeError::InvalidOperation(
+                                "find_all pattern cannot be empty".to_string().into(),
+                            ));
+                        }
+                        let mut offsets = vec![];
+                        let mut i = 0;
+                        while i + pat.len() <= b.len() {
+                            if b[i..i + pat.len()] == pat[..] {
+                                offsets.push(OnionObject::Integer(i as i64).stabilize());
+                                i += pat.len();
+                            } else {
+                                i += 1;
+                            }
+                        }
+                        Ok(OnionTuple::new_static_no_ref(&offsets))
+                    }
+                    _ => Err(RuntimeError::InvalidOperation(
+                        "find_all requires bytes arguments".to_string().into(),
+                    )),
+                })
+        })
+    })
+}
+
+/// Rotate bytes by n positions modulo the length
+fn rotate_bytes(
+    argument: &OnionStaticObject,
+    func_name: &str,
+    left: bool,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let bytes = get_attr_direct(data, "bytes".to_string())?;
+        let n = get_attr_direct(data, "n".to_string())?;
+
+        match (bytes.weak(), n.weak()) {
+            (OnionObject::Bytes(b), OnionObject::Integer(n)) => {
+                let mut result = b.as_ref().clone();
+                if !result.is_empty() {
+                    let shift = n.rem_euclid(result.len() as i64) as usize;
+                    if left {
+                        result.rotate_left(shift);
+                    } else {
+                        result.rotate_right(shift);
+                    }
+                }
+                Ok(OnionObject::Bytes(result.into()).stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                format!("{} requires bytes and integer arguments", func_name).into(),
+            )),
+        }
+    })
+}
+
+/// Rotate bytes left by n positions
+fn rotate_left(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    rotate_bytes(argument, "rotate_left", true)
+}
+
+/// Rotate bytes right by n positions
+fn rotate_right(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    rotate_bytes(argument, "rotate_right", false)
+}
+
+/// Count the set bits across all bytes
+fn popcount(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let bytes = get_attr_direct(data, "bytes".to_string())?;
+        bytes.weak().with_data(|bytes_data| match bytes_data {
+            OnionObject::Bytes(b) => {
+                let bits: u64 = b.iter().map(|byte| byte.count_ones() as u64).sum();
+                Ok(OnionObject::Integer(bits as i64).stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "popcount requires bytes".to_string().into(),
+            )),
+        })
+    })
+}
+
+/// Split bytes into unsigned integers of `width` bytes each
+fn read_int_array(
+    argument: &OnionStaticObject,
+    func_name: &str,
+    width: usize,
+) -> Result<OnionStaticObject, RuntimeError> {
+    use onion_vm::types::tuple::OnionTuple;
+
+    argument.weak().with_data(|data| {
+        let bytes = get_attr_direct(data, "bytes".to_string())?;
+        let big_endian = get_attr_direct(data, "big_endian".to_string())?;
+
+        match (bytes.weak(), big_endian.weak()) {
+            (OnionObject::Bytes(b), OnionObject::Boolean(big_endian)) => {
+                if b.len() % width != 0 {
+                    return Err(RuntimeError::InvalidOperation(
+                        format!(
+                            "{} requires a length divisible by {}, got {}",
+                            func_name,
+                            width,
+                            b.len()
+                        )
+                        .into(),
+                    ));
+                }
+                let values: Vec<_> = b
+                    .chunks_exact(width)
+                    .map(|chunk| {
+                        let value = if *big_endian {
+                            chunk
+                                .iter()
+                                .fold(0u64, |acc, &byte| (acc << 8) | byte as u64)
+                        } else {
+                            chunk
+                                .iter()
+                                .rev()
+                                .fold(0u64, |acc, &byte| (acc << 8) | byte as u64)
+                        };
+                        OnionObject::Integer(value as i64).stabilize()
+                    })
+                    .collect();
+                Ok(OnionTuple::new_static_no_ref(&values))
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                format!("{} requires bytes and boolean arguments", func_name).into(),
+            )),
+        }
+    })
+}
+
+/// Read bytes as a tuple of 16-bit unsigned integers
+fn read_u16_array(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    read_int_array(argument, "read_u16_array", 2)
+}
+
+/// Read bytes as a tuple of 32-bit unsigned integers
+fn read_u32_array(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    read_int_array(argument, "read_u32_array", 4)
+}
+
+/// Get bytes from start up to an exclusive end, counting negative offsets from the end
+fn sub(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let bytes = get_attr_direct(data, "bytes".to_string())?;
+        let start = get_attr_direct(data, "start".to_string())?;
+        let end = get_attr_direct(data, "end".to_string())?;
+
+        match (bytes.weak(), start.weak(), end.weak()) {
+            (OnionObject::Bytes(b), OnionObject::Integer(start), end) => {
+                let len = b.len() as i64;
+                let end = match end {
+                    OnionObject::Integer(end) => *end,
+                    OnionObject::Null => len,
+                    _ => {
+                        return Err(RuntimeError::InvalidOperation(
+                            "sub requires an integer or null end".to_string().into(),
+                        ))
+                    }
+                };
+                let resolve = |offset: i64| {
+                    if offset < 0 {
+                        (len + offset).max(0)
+                    } else {
+                        offset.min(len)
+                    }
+                };
+                let (start, end) = (resolve(*start) as usize, resolve(end) as usize);
+                let result = if start < end {
+                    b[start..end].to_vec()
+                } else {
+                    Vec::new()
+                };
+                Ok(OnionObject::Bytes(result.into()).stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "sub requires bytes and integer arguments"
+                    .to_string()
+                    .into(),
+            )),
+        }
+    })
+}
+
+/// Sum all bytes modulo the given modulus
+fn additive_checksum(
+    argument: &OnionStaticObject,
+    func_name: &str,
+    modulus: u64,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let bytes = get_attr_direct(data, "bytes".to_string())?;
+        bytes.weak().with_data(|bytes_data| match bytes_data {
+            OnionObject::Bytes(b) => {
+                let sum = b
+                    .iter()
+                    .fold(0u64, |acc, &byte| (acc + byte as u64) % modulus);
+                Ok(OnionObject::Integer(sum as i64).stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                format!("{} requires bytes", func_name).into(),
+            )),
+        })
+    })
+}
+
+/// Sum of all bytes modulo 256
+fn checksum8(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    additive_checksum(argument, "checksum8", 1 << 8)
+}
+
+/// Sum of all bytes modulo 65536
+fn checksum16(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    additive_checksum(argument, "checksum16", 1 << 16)
+}
+
+/// XOR data against a key repeated to cover its whole length
+fn xor_cipher(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let bytes = get_attr_direct(data, "data".to_string())?;
+        let key = get_attr_direct(data, "key".to_string())?;
+
+        match (bytes.weak(), key.weak()) {
+            (OnionObject::Bytes(b), OnionObject::Bytes(k)) => {
+                if k.is_empty() {
+                    return Err(RuntimeError::InvalidOperation(
+                        "xor_cipher requires a non-empty key".to_string().into(),
+                    ));
+                }
+                let result: Vec<u8> = b
+                    .iter()
+                    .zip(k.iter().cycle())
+                    .map(|(byte, key_byte)| byte ^ key_byte)
+                    .collect();
+                Ok(OnionObject::Bytes(result.into()).stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "xor_cipher requires bytes arguments".to_string().into(),
+            )),
+        }
+    })
+}
+
 pub fn build_module() -> OnionStaticObject {
     let mut module = IndexMap::new();
 
@@ -902,5 +1315,299 @@ pub fn build_module() -> OnionStaticObject {
         ),
     );
 
+    // concat_all 函数
+    let mut concat_all_params = IndexMap::new();
+    concat_all_params.insert(
+        "parts".to_string(),
+        OnionObject::Undefined(Some(
+            "Tuple of byte arrays to concatenate".to_string().into(),
+        ))
+        .stabilize(),
+    );
+    module.insert(
+        "concat_all".to_string(),
+        wrap_native_function(
+            &build_named_dict(concat_all_params),
+            None,
+            None,
+            "bytes::concat_all".to_string(),
+            &concat_all,
+        ),
+    );
+
+    // common_prefix_len 函数
+    let mut common_prefix_len_params = IndexMap::new();
+    common_prefix_len_params.insert(
+        "a".to_string(),
+        OnionObject::Undefined(Some("First byte array".to_string().into())).stabilize(),
+    );
+    common_prefix_len_params.insert(
+        "b".to_string(),
+        OnionObject::Undefined(Some("Second byte array".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "common_prefix_len".to_string(),
+        wrap_native_function(
+            &build_named_dict(common_prefix_len_params),
+            None,
+            None,
+            "bytes::common_prefix_len".to_string(),
+            &common_prefix_len,
+        ),
+    );
+
+    // to_string_lossy 函数
+    let mut to_string_lossy_params = IndexMap::new();
+    to_string_lossy_params.insert(
+        "bytes".to_string(),
+        OnionObject::Undefined(Some("Bytes to decode".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "to_string_lossy".to_string(),
+        wrap_native_function(
+            &build_named_dict(to_string_lossy_params),
+            None,
+            None,
+            "bytes::to_string_lossy".to_string(),
+            &to_string_lossy,
+        ),
+    );
+
+    // pad_to_multiple 函数
+    let mut pad_to_multiple_params = IndexMap::new();
+    pad_to_multiple_params.insert(
+        "bytes".to_string(),
+        OnionObject::Undefined(Some("Bytes to pad".to_string().into())).stabilize(),
+    );
+    pad_to_multiple_params.insert(
+        "block_size".to_string(),
+        OnionObject::Undefined(Some(
+            "Block size the length must be a multiple of"
+                .to_string()
+                .into(),
+        ))
+        .stabilize(),
+    );
+    pad_to_multiple_params.insert(
+        "pad_byte".to_string(),
+        OnionObject::Undefined(Some("Byte value to pad with (0-255)".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "pad_to_multiple".to_string(),
+        wrap_native_function(
+            &build_named_dict(pad_to_multiple_params),
+            None,
+            None,
+            "bytes::pad_to_multiple".to_string(),
+            &pad_to_multiple,
+        ),
+    );
+
+    // find_all 函数
+    let mut find_all_params = IndexMap::new();
+    find_all_params.insert(
+        "bytes".to_string(),
+        OnionObject::Undefined(Some("Bytes to search".to_string().into())).stabilize(),
+    );
+    find_all_params.insert(
+        "pattern".to_string(),
+        OnionObject::Undefined(Some("Byte pattern to find".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "find_all".to_string(),
+        wrap_native_function(
+            &build_named_dict(find_all_params),
+            None,
+            None,
+            "bytes::find_all".to_string(),
+            &find_all,
+        ),
+    );
+
+    // rotate_left 函数
+    let mut rotate_left_params = IndexMap::new();
+    rotate_left_params.insert(
+        "bytes".to_string(),
+        OnionObject::Undefined(Some("Bytes to rotate".to_string().into())).stabilize(),
+    );
+    rotate_left_params.insert(
+        "n".to_string(),
+        OnionObject::Undefined(Some("Number of positions".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "rotate_left".to_string(),
+        wrap_native_function(
+            &build_named_dict(rotate_left_params),
+            None,
+            None,
+            "bytes::rotate_left".to_string(),
+            &rotate_left,
+        ),
+    );
+
+    // rotate_right 函数
+    let mut rotate_right_params = IndexMap::new();
+    rotate_right_params.insert(
+        "bytes".to_string(),
+        OnionObject::Undefined(Some("Bytes to rotate".to_string().into())).stabilize(),
+    );
+    rotate_right_params.insert(
+        "n".to_string(),
+        OnionObject::Undefined(Some("Number of positions".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "rotate_right".to_string(),
+        wrap_native_function(
+            &build_named_dict(rotate_right_params),
+            None,
+            None,
+            "bytes::rotate_right".to_string(),
+            &rotate_right,
+        ),
+    );
+
+    // popcount 函数
+    let mut popcount_params = IndexMap::new();
+    popcount_params.insert(
+        "bytes".to_string(),
+        OnionObject::Undefined(Some("Bytes to count bits in".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "popcount".to_string(),
+        wrap_native_function(
+            &build_named_dict(popcount_params),
+            None,
+            None,
+            "bytes::popcount".to_string(),
+            &popcount,
+        ),
+    );
+
+    // read_u16_array 函数
+    let mut read_u16_array_params = IndexMap::new();
+    read_u16_array_params.insert(
+        "bytes".to_string(),
+        OnionObject::Undefined(Some("Bytes to read".to_string().into())).stabilize(),
+    );
+    read_u16_array_params.insert(
+        "big_endian".to_string(),
+        OnionObject::Boolean(false).stabilize(),
+    );
+    module.insert(
+        "read_u16_array".to_string(),
+        wrap_native_function(
+            &build_named_dict(read_u16_array_params),
+            None,
+            None,
+            "bytes::read_u16_array".to_string(),
+            &read_u16_array,
+        ),
+    );
+
+    // read_u32_array 函数
+    let mut read_u32_array_params = IndexMap::new();
+    read_u32_array_params.insert(
+        "bytes".to_string(),
+        OnionObject::Undefined(Some("Bytes to read".to_string().into())).stabilize(),
+    );
+    read_u32_array_params.insert(
+        "big_endian".to_string(),
+        OnionObject::Boolean(false).stabilize(),
+    );
+    module.insert(
+        "read_u32_array".to_string(),
+        wrap_native_function(
+            &build_named_dict(read_u32_array_params),
+            None,
+            None,
+            "bytes::read_u32_array".to_string(),
+            &read_u32_array,
+        ),
+    );
+
+    // sub 函数
+    let mut sub_params = IndexMap::new();
+    sub_params.insert(
+        "bytes".to_string(),
+        OnionObject::Undefined(Some("Source bytes".to_string().into())).stabilize(),
+    );
+    sub_params.insert(
+        "start".to_string(),
+        OnionObject::Undefined(Some(
+            "Start offset, negative counts from the end"
+                .to_string()
+                .into(),
+        ))
+        .stabilize(),
+    );
+    sub_params.insert("end".to_string(), OnionObject::Null.stabilize());
+    module.insert(
+        "sub".to_string(),
+        wrap_native_function(
+            &build_named_dict(sub_params),
+            None,
+            None,
+            "bytes::sub".to_string(),
+            &sub,
+        ),
+    );
+
+    // checksum8 函数
+    let mut checksum8_params = IndexMap::new();
+    checksum8_params.insert(
+        "bytes".to_string(),
+        OnionObject::Undefined(Some("Bytes to sum".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "checksum8".to_string(),
+        wrap_native_function(
+            &build_named_dict(checksum8_params),
+            None,
+            None,
+            "bytes::checksum8".to_string(),
+            &checksum8,
+        ),
+    );
+
+    // checksum16 函数
+    let mut checksum16_params = IndexMap::new();
+    checksum16_params.insert(
+        "bytes".to_string(),
+        OnionObject::Undefined(Some("Bytes to sum".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "checksum16".to_string(),
+        wrap_native_function(
+            &build_named_dict(checksum16_params),
+            None,
+            None,
+            "bytes::checksum16".to_string(),
+            &checksum16,
+        ),
+    );
+
+    // xor_cipher 函数
+    let mut xor_cipher_params = IndexMap::new();
+    xor_cipher_params.insert(
+        "data".to_string(),
+        OnionObject::Undefined(Some("Bytes to encrypt or decrypt".to_string().into()))
+            .stabilize(),
+    );
+    xor_cipher_params.insert(
+        "key".to_string(),
+        OnionObject::Undefined(Some("Key bytes, repeated as needed".to_string().into()))
+            .stabilize(),
+    );
+    module.insert(
+        "xor_cipher".to_string(),
+        wrap_native_function(
+            &build_named_dict(xor_cipher_params),
+            None,
+            None,
+            "bytes::xor_cipher".to_string(),
+            &xor_cipher,
+        ),
+    );
+
     build_named_dict(module)
 }
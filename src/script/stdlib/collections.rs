@@ -0,0 +1,296 @@
+use std::{
+    collections::VecDeque,
+    fmt::Debug,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use arc_gc::{
+    arc::{GCArc, GCArcWeak},
+    traceable::GCTraceable,
+};
+use indexmap::IndexMap;
+use onion_vm::{
+    lambda::runnable::RuntimeError,
+    onion_tuple,
+    types::{
+        object::{OnionObject, OnionObjectCell, OnionObjectExt, OnionStaticObject},
+        tuple::OnionTuple,
+    },
+    GC,
+};
+
+use super::{
+    build_named_dict, get_attr_direct, with_self_custom, wrap_native_function,
+    wrap_native_method_function,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CollectionKind {
+    Stack,
+    Queue,
+}
+
+/// Mutable stack or queue shared by every copy of the wrapping object
+pub struct OnionCollection {
+    kind: CollectionKind,
+    items: Arc<Mutex<VecDeque<OnionStaticObject>>>,
+}
+
+impl OnionCollection {
+    fn lock(&self) -> Result<MutexGuard<'_, VecDeque<OnionStaticObject>>, RuntimeError> {
+        self.items.lock().map_err(|_| {
+            RuntimeError::DetailedError("collection lock is poisoned".to_string().into())
+        })
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self.kind {
+            CollectionKind::Stack => "Stack",
+            CollectionKind::Queue => "Queue",
+        }
+    }
+}
+
+impl Debug for OnionCollection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "OnionCollection({:?})", self.kind)
+    }
+}
+
+impl GCTraceable<OnionObjectCell> for OnionCollection {
+    fn collect(&self, _: &mut VecDeque<GCArcWeak<OnionObjectCell>>) {}
+}
+
+impl OnionObjectExt for OnionCollection {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn upgrade(&self, _: &mut Vec<GCArc<OnionObjectCell>>) {
+        // items are stored as static objects and keep themselves alive
+    }
+
+    fn equals(&self, other: &OnionObject) -> Result<bool, RuntimeError> {
+        self.is_same(other)
+    }
+
+    fn is_same(&self, other: &OnionObject) -> Result<bool, RuntimeError> {
+        match other {
+            OnionObject::Custom(custom) => Ok(custom
+                .as_any()
+                .downcast_ref::<OnionCollection>()
+                .is_some_and(|other| Arc::ptr_eq(&self.items, &other.items))),
+            _ => Ok(false),
+        }
+    }
+
+    fn type_of(&self) -> Result<String, RuntimeError> {
+        Ok(self.type_name().to_string())
+    }
+
+    fn repr(&self, ptrs: &Vec<*const OnionObject>) -> Result<String, RuntimeError> {
+        self.to_string(ptrs)
+    }
+
+    fn to_string(&self, ptrs: &Vec<*const OnionObject>) -> Result<String, RuntimeError> {
+        let items = self.lock()?;
+        let mut parts = Vec::with_capacity(items.len());
+        for item in items.iter() {
+            parts.push(item.weak().to_string(ptrs)?);
+        }
+        Ok(format!("{}[{}]", self.type_name(), parts.join(", ")))
+    }
+}
+
+/// Add a value to the collection
+fn push(
+    self_object: Option<&OnionStaticObject>,
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    let value = argument
+        .weak()
+        .with_data(|data| get_attr_direct(data, "value".to_string()))?;
+    with_self_custom(self_object, |collection: &OnionCollection| {
+        collection.lock()?.push_back(value.clone());
+        Ok(OnionObject::Null.stabilize())
+    })
+}
+
+/// Remove and return the top of a stack or the front of a queue
+fn pop(
+    self_object: Option<&OnionStaticObject>,
+    _argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    with_self_custom(self_object, |collection: &OnionCollection| {
+        let mut items = collection.lock()?;
+        let item = match collection.kind {
+            CollectionKind::Stack => items.pop_back(),
+            CollectionKind::Queue => items.pop_front(),
+        };
+        item.ok_or_else(|| {
+            RuntimeError::InvalidOperation(
+                format!("pop from empty {}", collection.type_name().to_lowercase()).into(),
+            )
+        })
+    })
+}
+
+/// Return the item `pop` would remove without removing it
+fn peek(
+    self_object: Option<&OnionStaticObject>,
+    _argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    with_self_custom(self_object, |collection: &OnionCollection| {
+        let items = collection.lock()?;
+        let item = match collection.kind {
+            CollectionKind::Stack => items.back(),
+            CollectionKind::Queue => items.front(),
+        };
+        item.cloned().ok_or_else(|| {
+            RuntimeError::InvalidOperation(
+                format!("peek at empty {}", collection.type_name().to_lowercase()).into(),
+            )
+        })
+    })
+}
+
+/// Number of items in the collection
+fn len(
+    self_object: Option<&OnionStaticObject>,
+    _argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    with_self_custom(self_object, |collection: &OnionCollection| {
+        Ok(OnionObject::Integer(collection.lock()?.len() as i64).stabilize())
+    })
+}
+
+/// Items in the collection, in insertion order
+fn to_tuple(
+    self_object: Option<&OnionStaticObject>,
+    _argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    with_self_custom(self_object, |collection: &OnionCollection| {
+        let items: Vec<_> = collection.lock()?.iter().cloned().collect();
+        Ok(OnionTuple::new_static_no_ref(&items))
+    })
+}
+
+/// Build an object exposing the collection's methods bound to it
+fn new_collection(kind: CollectionKind) -> OnionStaticObject {
+    let handle = OnionObject::Custom(Arc::new(OnionCollection {
+        kind,
+        items: Arc::new(Mutex::new(VecDeque::new())),
+    }))
+    .stabilize();
+
+    let mut object = IndexMap::new();
+    let mut push_params = IndexMap::new();
+    push_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some("Value to add".to_string().into())).stabilize(),
+    );
+    object.insert(
+        "push".to_string(),
+        wrap_native_method_function(
+            &build_named_dict(push_params),
+            None,
+            Some(&handle),
+            "collections::push".to_string(),
+            &push,
+        ),
+    );
+    object.insert(
+        "pop".to_string(),
+        wrap_native_method_function(
+            &onion_tuple!(),
+            None,
+            Some(&handle),
+            "collections::pop".to_string(),
+            &pop,
+        ),
+    );
+    object.insert(
+        "peek".to_string(),
+        wrap_native_method_function(
+            &onion_tuple!(),
+            None,
+            Some(&handle),
+            "collections::peek".to_string(),
+            &peek,
+        ),
+    );
+    object.insert(
+        "len".to_string(),
+        wrap_native_method_function(
+            &onion_tuple!(),
+            None,
+            Some(&handle),
+            "collections::len".to_string(),
+            &len,
+        ),
+    );
+    object.insert(
+        "to_tuple".to_string(),
+        wrap_native_method_function(
+            &onion_tuple!(),
+            None,
+            Some(&handle),
+            "collections::to_tuple".to_string(),
+            &to_tuple,
+        ),
+    );
+    object.insert("handle".to_string(), handle);
+    build_named_dict(object)
+}
+
+/// Create an empty last-in first-out stack
+fn new_stack(
+    _argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    Ok(new_collection(CollectionKind::Stack))
+}
+
+/// Create an empty first-in first-out queue
+fn new_queue(
+    _argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    Ok(new_collection(CollectionKind::Queue))
+}
+
+/// Build the stateful collections module
+pub fn build_module() -> OnionStaticObject {
+    let mut module = IndexMap::new();
+
+    // new_stack 函数
+    module.insert(
+        "new_stack".to_string(),
+        wrap_native_function(
+            &onion_tuple!(),
+            None,
+            None,
+            "collections::new_stack".to_string(),
+            &new_stack,
+        ),
+    );
+
+    // new_queue 函数
+    module.insert(
+        "new_queue".to_string(),
+        wrap_native_function(
+            &onion_tuple!(),
+            None,
+            None,
+            "collections::new_queue".to_string(),
+            &new_queue,
+        ),
+    );
+
+    build_named_dict(module)
+}
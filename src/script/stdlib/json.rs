@@ -0,0 +1,153 @@
+use indexmap::IndexMap;
+use onion_vm::{
+    lambda::runnable::RuntimeError,
+    types::{
+        object::{OnionObject, OnionObjectCell, OnionStaticObject},
+        tuple::OnionTuple,
+    },
+    GC,
+};
+use serde_json::{Map, Number, Value};
+
+use super::{build_named_dict, get_attr_direct, wrap_native_function};
+
+/// Convert a JSON value into Onion objects; objects become tuples of named entries
+fn from_json(value: Value) -> OnionStaticObject {
+    match value {
+        Value::Null => OnionObject::Null.stabilize(),
+        Value::Bool(b) => OnionObject::Boolean(b).stabilize(),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => OnionObject::Integer(i).stabilize(),
+            None => OnionObject::Float(n.as_f64().unwrap_or(f64::NAN)).stabilize(),
+        },
+        Value::String(s) => OnionObject::String(s.into()).stabilize(),
+        Value::Array(items) => {
+            let elements: Vec<_> = items.into_iter().map(from_json).collect();
+            OnionTuple::new_static_no_ref(&elements)
+        }
+        Value::Object(entries) => {
+            let mut dict = IndexMap::new();
+            for (key, value) in entries {
+                dict.insert(key, from_json(value));
+            }
+            build_named_dict(dict)
+        }
+    }
+}
+
+/// Convert an Onion object into a JSON value
+fn to_json(obj: &OnionObject) -> Result<Value, RuntimeError> {
+    match obj {
+        OnionObject::Null => Ok(Value::Null),
+        OnionObject::Boolean(b) => Ok(Value::Bool(*b)),
+        OnionObject::Integer(i) => Ok(Value::Number((*i).into())),
+        OnionObject::Float(f) => Number::from_f64(*f).map(Value::Number).ok_or_else(|| {
+            RuntimeError::InvalidOperation(
+                format!("stringify cannot represent {} in JSON", f).into(),
+            )
+        }),
+        OnionObject::String(s) => Ok(Value::String(s.as_ref().clone())),
+        OnionObject::Tuple(tuple) => {
+            let elements = tuple.get_elements();
+            let named = elements
+                .iter()
+                .filter(|element| matches!(element, OnionObject::Named(_)))
+                .count();
+            if named == 0 {
+                return elements
+                    .iter()
+                    .map(|element| element.with_data(to_json))
+                    .collect::<Result<_, _>>()
+                    .map(Value::Array);
+            }
+            if named != elements.len() {
+                return Err(RuntimeError::InvalidOperation(
+                    "stringify cannot mix named and positional elements"
+                        .to_string()
+                        .into(),
+                ));
+            }
+            let mut entries = Map::new();
+            for element in elements {
+                let key = element.key_of()?.weak().to_string(&vec![])?;
+                let value = element.value_of()?.weak().with_data(to_json)?;
+                entries.insert(key, value);
+            }
+            Ok(Value::Object(entries))
+        }
+        _ => Err(RuntimeError::InvalidOperation(
+            format!("stringify cannot represent {} in JSON", obj.type_of()?).into(),
+        )),
+    }
+}
+
+/// Parse a JSON document
+fn parse(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let string = get_attr_direct(data, "string".to_string())?;
+        string.weak().with_data(|string_data| match string_data {
+            OnionObject::String(s) => serde_json::from_str::<Value>(s)
+                .map(from_json)
+                .map_err(|e| RuntimeError::InvalidOperation(format!("Invalid JSON: {}", e).into())),
+            _ => Err(RuntimeError::InvalidOperation(
+                "parse requires string".to_string().into(),
+            )),
+        })
+    })
+}
+
+/// Serialize a value to a compact JSON string
+fn stringify(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let value = get_attr_direct(data, "value".to_string())?;
+        let json = value.weak().with_data(to_json)?;
+        Ok(OnionObject::String(json.to_string().into()).stabilize())
+    })
+}
+
+/// Build the json module
+pub fn build_module() -> OnionStaticObject {
+    let mut module = IndexMap::new();
+
+    // parse 函数
+    let mut parse_params = IndexMap::new();
+    parse_params.insert(
+        "string".to_string(),
+        OnionObject::Undefined(Some("JSON document to parse".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "parse".to_string(),
+        wrap_native_function(
+            &build_named_dict(parse_params),
+            None,
+            None,
+            "json::parse".to_string(),
+            &parse,
+        ),
+    );
+
+    // stringify 函数
+    let mut stringify_params = IndexMap::new();
+    stringify_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some("Value to serialize".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "stringify".to_string(),
+        wrap_native_function(
+            &build_named_dict(stringify_params),
+            None,
+            None,
+            "json::stringify".to_string(),
+            &stringify,
+        ),
+    );
+
+    build_named_dict(module)
+}
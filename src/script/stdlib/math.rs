@@ -1,11 +1,30 @@
+use std::{
+    collections::VecDeque,
+    fmt::Debug,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use arc_gc::{
+    arc::{GCArc, GCArcWeak},
+    traceable::GCTraceable,
+};
 use indexmap::IndexMap;
 use onion_vm::{
     lambda::runnable::RuntimeError,
-    types::object::{OnionObject, OnionObjectCell, OnionStaticObject},
+    onion_tuple,
+    types::{
+        lambda::definition::{LambdaBody, OnionLambdaDefinition},
+        object::{OnionObject, OnionObjectCell, OnionObjectExt, OnionStaticObject},
+        pair::OnionPair,
+        tuple::OnionTuple,
+    },
     GC,
 };
 
-use super::{build_named_dict, get_attr_direct, wrap_native_function};
+use super::{
+    build_named_dict, get_attr_direct, with_self_custom, wrap_native_function,
+    wrap_native_method_function, CallStep, LambdaCaller, LambdaCalling,
+};
 
 fn abs(
     argument: &OnionStaticObject,
@@ -321,153 +340,1395 @@ fn atan(
     })
 }
 
-pub fn build_module() -> OnionStaticObject {
-    let mut module = IndexMap::new();
+/// Collect the elements of a numeric tuple as f64 values
+fn numeric_elements(obj: &OnionObject, func_name: &str) -> Result<Vec<f64>, RuntimeError> {
+    match obj {
+        OnionObject::Tuple(tuple) => tuple
+            .get_elements()
+            .iter()
+            .map(|element| match element {
+                OnionObject::Integer(n) => Ok(*n as f64),
+                OnionObject::Float(f) => Ok(*f),
+                _ => Err(RuntimeError::InvalidOperation(
+                    format!("{} requires a tuple of numbers", func_name).into(),
+                )),
+            })
+            .collect(),
+        _ => Err(RuntimeError::InvalidOperation(
+            format!("{} requires a tuple of numbers", func_name).into(),
+        )),
+    }
+}
 
-    // 数学常量
-    module.insert(
-        "PI".to_string(),
-        OnionObject::Float(std::f64::consts::PI).stabilize(),
-    );
-    module.insert(
-        "E".to_string(),
-        OnionObject::Float(std::f64::consts::E).stabilize(),
-    );
+/// Read a numeric value as f64
+fn numeric_value(obj: &OnionObject, func_name: &str) -> Result<f64, RuntimeError> {
+    match obj {
+        OnionObject::Integer(n) => Ok(*n as f64),
+        OnionObject::Float(f) => Ok(*f),
+        _ => Err(RuntimeError::InvalidOperation(
+            format!("{} requires numeric values", func_name).into(),
+        )),
+    }
+}
 
-    // abs 函数
-    let mut abs_params = IndexMap::new();
-    abs_params.insert(
-        "value".to_string(),
-        OnionObject::Undefined(Some("Number to get absolute value".to_string().into())).stabilize(),
-    );
-    module.insert(
-        "abs".to_string(),
-        wrap_native_function(
-            &build_named_dict(abs_params),
-            None,
-            None,
-            "math::abs".to_string(),
-            &abs,
-        ),
-    ); // sin 函数
-    let mut sin_params = IndexMap::new();
-    sin_params.insert(
-        "value".to_string(),
-        OnionObject::Undefined(Some("Angle in radians".to_string().into())).stabilize(),
-    );
-    module.insert(
-        "sin".to_string(),
-        wrap_native_function(
-            &build_named_dict(sin_params),
-            None,
-            None,
-            "math::sin".to_string(),
-            &sin,
-        ),
-    );
+/// Dot product of two equal-length numeric tuples
+fn dot(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let a = get_attr_direct(data, "a".to_string())?;
+        let b = get_attr_direct(data, "b".to_string())?;
 
-    // cos 函数
-    let mut cos_params = IndexMap::new();
-    cos_params.insert(
-        "value".to_string(),
-        OnionObject::Undefined(Some("Angle in radians".to_string().into())).stabilize(),
-    );
-    module.insert(
-        "cos".to_string(),
-        wrap_native_function(
-            &build_named_dict(cos_params),
-            None,
-            None,
-            "math::cos".to_string(),
-            &cos,
-        ),
-    );
+        a.weak().with_data(|a_data| {
+            b.weak().with_data(|b_data| match (a_data, b_data) {
+                (OnionObject::Tuple(ta), OnionObject::Tuple(tb)) => {
+                    let (ea, eb) = (ta.get_elements(), tb.get_elements());
+                    if ea.len() != eb.len() {
+                        return Err(RuntimeError::InvalidOperation(
+                            format!(
+                                "dot requires tuples of equal length, got {} and {}",
+                                ea.len(),
+                                eb.len()
+                            )
+                            .into(),
+                        ));
+                    }
+                    let all_integers = ea
+                        .iter()
+                        .chain(eb.iter())
+                        .all(|element| matches!(element, OnionObject::Integer(_)));
+                    if all_integers {
+                        let mut sum: i64 = 0;
+                        for (x, y) in ea.iter().zip(eb.iter()) {
+                            if let (OnionObject::Integer(x), OnionObject::Integer(y)) = (x, y) {
+                                sum = x
+                                    .checked_mul(*y)
+                                    .and_then(|product| sum.checked_add(product))
+                                    .ok_or_else(|| {
+                                        RuntimeError::InvalidOperation(
+                                            "dot overflowed the integer range".to_string().into(),
+                                        )
+                                    })?;
+                            }
+                        }
+                        return Ok(OnionObject::Integer(sum).stabilize());
+                    }
+                    let va = numeric_elements(a_data, "dot")?;
+                    let vb = numeric_elements(b_data, "dot")?;
+                    let sum: f64 = va.iter().zip(vb.iter()).map(|(x, y)| x * y).sum();
+                    Ok(OnionObject::Float(sum).stabilize())
+                }
+                _ => Err(RuntimeError::InvalidOperation(
+                    "dot requires tuple arguments".to_string().into(),
+                )),
+            })
+        })
+    })
+}
 
-    // tan 函数
-    let mut tan_params = IndexMap::new();
-    tan_params.insert(
-        "value".to_string(),
-        OnionObject::Undefined(Some("Angle in radians".to_string().into())).stabilize(),
-    );
-    module.insert(
-        "tan".to_string(),
-        wrap_native_function(
-            &build_named_dict(tan_params),
-            None,
-            None,
-            "math::tan".to_string(),
-            &tan,
-        ),
-    );
+/// Euclidean norm of a numeric tuple
+fn norm(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let vector = get_attr_direct(data, "vector".to_string())?;
+        vector.weak().with_data(|vector_data| {
+            let values = numeric_elements(vector_data, "norm")?;
+            let sum_squares: f64 = values.iter().map(|v| v * v).sum();
+            Ok(OnionObject::Float(sum_squares.sqrt()).stabilize())
+        })
+    })
+}
 
-    // log 函数
-    let mut log_params = IndexMap::new();
-    log_params.insert(
-        "value".to_string(),
-        OnionObject::Undefined(Some(
-            "Number to calculate natural logarithm".to_string().into(),
-        ))
-        .stabilize(),
-    );
-    module.insert(
-        "log".to_string(),
-        wrap_native_function(
-            &build_named_dict(log_params),
-            None,
-            None,
-            "math::log".to_string(),
-            &log,
-        ),
-    );
+/// Normalize an angle in degrees into [0, 360)
+fn clamp_angle(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let value = get_attr_direct(data, "value".to_string())?;
+        value.weak().with_data(|value_data| match value_data {
+            OnionObject::Integer(n) => Ok(OnionObject::Integer(n.rem_euclid(360)).stabilize()),
+            OnionObject::Float(f) => Ok(OnionObject::Float(f.rem_euclid(360.0)).stabilize()),
+            _ => Err(RuntimeError::InvalidOperation(
+                "clamp_angle requires numeric value".to_string().into(),
+            )),
+        })
+    })
+}
 
-    // exp 函数
-    let mut exp_params = IndexMap::new();
-    exp_params.insert(
-        "value".to_string(),
-        OnionObject::Undefined(Some("Exponent for e^x".to_string().into())).stabilize(),
+/// Smallest signed difference from angle a to angle b in degrees, in (-180, 180]
+fn angle_diff(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let a = get_attr_direct(data, "a".to_string())?;
+        let b = get_attr_direct(data, "b".to_string())?;
+
+        a.weak().with_data(|a_data| {
+            b.weak().with_data(|b_data| match (a_data, b_data) {
+                (OnionObject::Integer(a), OnionObject::Integer(b)) => {
+                    // i128 keeps b - a from overflowing; the remainder always fits in i64
+                    let diff = (*b as i128 - *a as i128).rem_euclid(360) as i64;
+                    let diff = if diff > 180 { diff - 360 } else { diff };
+                    Ok(OnionObject::Integer(diff).stabilize())
+                }
+                (
+                    OnionObject::Integer(_) | OnionObject::Float(_),
+                    OnionObject::Integer(_) | OnionObject::Float(_),
+                ) => {
+                    let diff = (b_data.to_float()? - a_data.to_float()?).rem_euclid(360.0);
+                    let diff = if diff > 180.0 { diff - 360.0 } else { diff };
+                    Ok(OnionObject::Float(diff).stabilize())
+                }
+                _ => Err(RuntimeError::InvalidOperation(
+                    "angle_diff requires numeric values".to_string().into(),
+                )),
+            })
+        })
+    })
+}
+
+/// Real roots of a*x^2 + b*x + c = 0 in ascending order
+fn solve_quadratic(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let a = get_attr_direct(data, "a".to_string())?;
+        let b = get_attr_direct(data, "b".to_string())?;
+        let c = get_attr_direct(data, "c".to_string())?;
+        let a = numeric_value(a.weak(), "solve_quadratic")?;
+        let b = numeric_value(b.weak(), "solve_quadratic")?;
+        let c = numeric_value(c.weak(), "solve_quadratic")?;
+
+        if a == 0.0 {
+            return Err(RuntimeError::InvalidOperation(
+                "solve_quadratic requires a != 0, use a linear solver for b*x + c = 0"
+                    .to_string()
+                    .into(),
+            ));
+        }
+
+        let discriminant = b * b - 4.0 * a * c;
+        let roots = if discriminant > 0.0 {
+            let sqrt_d = discriminant.sqrt();
+            let r1 = (-b - sqrt_d) / (2.0 * a);
+            let r2 = (-b + sqrt_d) / (2.0 * a);
+            vec![r1.min(r2), r1.max(r2)]
+        } else if discriminant == 0.0 {
+            vec![-b / (2.0 * a)]
+        } else {
+            vec![]
+        };
+        let roots: Vec<_> = roots
+            .into_iter()
+            .map(|root| OnionObject::Float(root).stabilize())
+            .collect();
+        Ok(OnionTuple::new_static_no_ref(&roots))
+    })
+}
+
+/// Piecewise-linear interpolation over a lookup table, clamped to the endpoints
+fn interp_table(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let x = get_attr_direct(data, "x".to_string())?;
+        let xs = get_attr_direct(data, "xs".to_string())?;
+        let ys = get_attr_direct(data, "ys".to_string())?;
+        let x = numeric_value(x.weak(), "interp_table")?;
+        let xs = numeric_elements(xs.weak(), "interp_table")?;
+        let ys = numeric_elements(ys.weak(), "interp_table")?;
+
+        if xs.len() != ys.len() {
+            return Err(RuntimeError::InvalidOperation(
+                format!(
+                    "interp_table requires xs and ys of equal length, got {} and {}",
+                    xs.len(),
+                    ys.len()
+                )
+                .into(),
+            ));
+        }
+        if xs.is_empty() {
+            return Err(RuntimeError::InvalidOperation(
+                "interp_table requires a non-empty table".to_string().into(),
+            ));
+        }
+        if xs.windows(2).any(|pair| pair[1] < pair[0]) {
+            return Err(RuntimeError::InvalidOperation(
+                "interp_table requires xs sorted in ascending order"
+                    .to_string()
+                    .into(),
+            ));
+        }
+
+        let last = xs.len() - 1;
+        let result = if x <= xs[0] {
+            ys[0]
+        } else if x >= xs[last] {
+            ys[last]
+        } else {
+            // xs[i] <= x < xs[i + 1]
+            let i = xs.partition_point(|&v| v <= x) - 1;
+            let span = xs[i + 1] - xs[i];
+            if span == 0.0 {
+                ys[i]
+            } else {
+                ys[i] + (ys[i + 1] - ys[i]) * (x - xs[i]) / span
+            }
+        };
+        Ok(OnionObject::Float(result).stabilize())
+    })
+}
+
+/// Snap a value to the nearest integer when it lies within epsilon of it
+fn snap(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let value = get_attr_direct(data, "value".to_string())?;
+        let epsilon = get_attr_direct(data, "epsilon".to_string())?;
+        let value = numeric_value(value.weak(), "snap")?;
+        let epsilon = numeric_value(epsilon.weak(), "snap")?;
+
+        if epsilon < 0.0 {
+            return Err(RuntimeError::InvalidOperation(
+                "snap requires a non-negative epsilon".to_string().into(),
+            ));
+        }
+
+        let nearest = value.round();
+        if (value - nearest).abs() <= epsilon {
+            Ok(OnionObject::Float(nearest).stabilize())
+        } else {
+            Ok(OnionObject::Float(value).stabilize())
+        }
+    })
+}
+
+/// Wrap a value cyclically into [min, max)
+fn wrap(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let value = get_attr_direct(data, "value".to_string())?;
+        let min = get_attr_direct(data, "min".to_string())?;
+        let max = get_attr_direct(data, "max".to_string())?;
+
+        if let (OnionObject::Integer(v), OnionObject::Integer(lo), OnionObject::Integer(hi)) =
+            (value.weak(), min.weak(), max.weak())
+        {
+            if hi <= lo {
+                return Err(RuntimeError::InvalidOperation(
+                    "wrap requires max > min".to_string().into(),
+                ));
+            }
+            // i128 keeps v - lo and hi - lo from overflowing; the result lies in [lo, hi)
+            let (v, lo, hi) = (*v as i128, *lo as i128, *hi as i128);
+            let wrapped = lo + (v - lo).rem_euclid(hi - lo);
+            return Ok(OnionObject::Integer(wrapped as i64).stabilize());
+        }
+
+        let v = numeric_value(value.weak(), "wrap")?;
+        let lo = numeric_value(min.weak(), "wrap")?;
+        let hi = numeric_value(max.weak(), "wrap")?;
+        if hi <= lo {
+            return Err(RuntimeError::InvalidOperation(
+                "wrap requires max > min".to_string().into(),
+            ));
+        }
+        Ok(OnionObject::Float(lo + (v - lo).rem_euclid(hi - lo)).stabilize())
+    })
+}
+
+/// Running mean state shared by every copy of the wrapping object
+pub struct OnionRunningMean {
+    state: Arc<Mutex<(u64, f64)>>,
+}
+
+impl OnionRunningMean {
+    fn lock(&self) -> Result<MutexGuard<'_, (u64, f64)>, RuntimeError> {
+        self.state.lock().map_err(|_| {
+            RuntimeError::DetailedError("running_mean lock is poisoned".to_string().into())
+        })
+    }
+}
+
+impl Debug for OnionRunningMean {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "OnionRunningMean({:?})", self.state)
+    }
+}
+
+impl GCTraceable<OnionObjectCell> for OnionRunningMean {
+    fn collect(&self, _: &mut VecDeque<GCArcWeak<OnionObjectCell>>) {}
+}
+
+impl OnionObjectExt for OnionRunningMean {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn upgrade(&self, _: &mut Vec<GCArc<OnionObjectCell>>) {
+        // nothing
+    }
+
+    fn equals(&self, other: &OnionObject) -> Result<bool, RuntimeError> {
+        self.is_same(other)
+    }
+
+    fn is_same(&self, other: &OnionObject) -> Result<bool, RuntimeError> {
+        match other {
+            OnionObject::Custom(custom) => Ok(custom
+                .as_any()
+                .downcast_ref::<OnionRunningMean>()
+                .is_some_and(|other| Arc::ptr_eq(&self.state, &other.state))),
+            _ => Ok(false),
+        }
+    }
+
+    fn type_of(&self) -> Result<String, RuntimeError> {
+        Ok("RunningMean".to_string())
+    }
+
+    fn repr(&self, ptrs: &Vec<*const OnionObject>) -> Result<String, RuntimeError> {
+        self.to_string(ptrs)
+    }
+
+    fn to_string(&self, _: &Vec<*const OnionObject>) -> Result<String, RuntimeError> {
+        let (count, sum) = *self.lock()?;
+        Ok(format!("RunningMean(count={}, sum={})", count, sum))
+    }
+}
+
+/// Add a sample to a running mean
+fn running_mean_add(
+    self_object: Option<&OnionStaticObject>,
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    let x = argument.weak().with_data(|data| {
+        let x = get_attr_direct(data, "x".to_string())?;
+        numeric_value(x.weak(), "running_mean.add")
+    })?;
+    with_self_custom(self_object, |mean: &OnionRunningMean| {
+        let mut state = mean.lock()?;
+        state.0 += 1;
+        state.1 += x;
+        Ok(OnionObject::Null.stabilize())
+    })
+}
+
+/// Current mean of the samples added so far
+fn running_mean_value(
+    self_object: Option<&OnionStaticObject>,
+    _argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    with_self_custom(self_object, |mean: &OnionRunningMean| {
+        let (count, sum) = *mean.lock()?;
+        if count == 0 {
+            return Err(RuntimeError::InvalidOperation(
+                "running_mean has no samples".to_string().into(),
+            ));
+        }
+        Ok(OnionObject::Float(sum / count as f64).stabilize())
+    })
+}
+
+/// Number of samples added so far
+fn running_mean_count(
+    self_object: Option<&OnionStaticObject>,
+    _argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    with_self_custom(self_object, |mean: &OnionRunningMean| {
+        Ok(OnionObject::Integer(mean.lock()?.0 as i64).stabilize())
+    })
+}
+
+/// Create a stateful averager with `add(x)`, `value()` and `count()` methods
+fn running_mean(
+    _argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    let handle = OnionObject::Custom(Arc::new(OnionRunningMean {
+        state: Arc::new(Mutex::new((0, 0.0))),
+    }))
+    .stabilize();
+
+    let mut object = IndexMap::new();
+    let mut add_params = IndexMap::new();
+    add_params.insert(
+        "x".to_string(),
+        OnionObject::Undefined(Some("Sample to add".to_string().into())).stabilize(),
     );
-    module.insert(
-        "exp".to_string(),
-        wrap_native_function(
-            &build_named_dict(exp_params),
-            None,
+    object.insert(
+        "add".to_string(),
+        wrap_native_method_function(
+            &build_named_dict(add_params),
             None,
-            "math::exp".to_string(),
-            &exp,
+            Some(&handle),
+            "math::running_mean::add".to_string(),
+            &running_mean_add,
         ),
     );
-
-    // floor 函数
-    let mut floor_params = IndexMap::new();
-    floor_params.insert(
+    object.insert(
         "value".to_string(),
-        OnionObject::Undefined(Some("Number to floor".to_string().into())).stabilize(),
-    );
-    module.insert(
-        "floor".to_string(),
-        wrap_native_function(
-            &build_named_dict(floor_params),
+        wrap_native_method_function(
+            &onion_tuple!(),
             None,
-            None,
-            "math::floor".to_string(),
-            &floor,
+            Some(&handle),
+            "math::running_mean::value".to_string(),
+            &running_mean_value,
         ),
     );
-
-    // ceil 函数
-    let mut ceil_params = IndexMap::new();
-    ceil_params.insert(
-        "value".to_string(),
-        OnionObject::Undefined(Some("Number to ceil".to_string().into())).stabilize(),
-    );
-    module.insert(
-        "ceil".to_string(),
-        wrap_native_function(
-            &build_named_dict(ceil_params),
+    object.insert(
+        "count".to_string(),
+        wrap_native_method_function(
+            &onion_tuple!(),
             None,
-            None,
-            "math::ceil".to_string(),
-            &ceil,
+            Some(&handle),
+            "math::running_mean::count".to_string(),
+            &running_mean_count,
+        ),
+    );
+    object.insert("handle".to_string(), handle);
+    Ok(build_named_dict(object))
+}
+
+/// Resolve a possibly negative index against a length and clamp it to [0, length - 1]
+fn clamp_index(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let index = get_attr_direct(data, "index".to_string())?;
+        let length = get_attr_direct(data, "length".to_string())?;
+
+        match (index.weak(), length.weak()) {
+            (OnionObject::Integer(index), OnionObject::Integer(length)) => {
+                if *length <= 0 {
+                    return Err(RuntimeError::InvalidOperation(
+                        "clamp_index requires a positive length".to_string().into(),
+                    ));
+                }
+                let resolved = if *index < 0 {
+                    index.checked_add(*length).ok_or_else(|| {
+                        RuntimeError::InvalidOperation(
+                            "clamp_index overflowed the integer range"
+                                .to_string()
+                                .into(),
+                        )
+                    })?
+                } else {
+                    *index
+                };
+                Ok(OnionObject::Integer(resolved.clamp(0, length - 1)).stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "clamp_index requires integer arguments".to_string().into(),
+            )),
+        }
+    })
+}
+
+/// Evaluate a polynomial (coefficients highest degree first) at x using Horner's method
+fn poly_eval(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let coeffs = get_attr_direct(data, "coeffs".to_string())?;
+        let x = get_attr_direct(data, "x".to_string())?;
+        let coeffs = coeffs
+            .weak()
+            .with_data(|coeffs_data| numeric_elements(coeffs_data, "poly_eval"))?;
+        let x = numeric_value(x.weak(), "poly_eval")?;
+        let result = coeffs.iter().fold(0.0, |acc, c| acc * x + c);
+        Ok(OnionObject::Float(result).stabilize())
+    })
+}
+
+/// Clamp every element of a numeric tuple to [low, high]
+fn clamp_each(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let values = get_attr_direct(data, "values".to_string())?;
+        let low = get_attr_direct(data, "low".to_string())?;
+        let high = get_attr_direct(data, "high".to_string())?;
+        let (low_f, high_f) = (
+            numeric_value(low.weak(), "clamp_each")?,
+            numeric_value(high.weak(), "clamp_each")?,
+        );
+        if low_f.is_nan() || high_f.is_nan() {
+            return Err(RuntimeError::InvalidOperation(
+                "clamp_each bounds cannot be NaN".to_string().into(),
+            ));
+        }
+        // compare integer bounds exactly; distinct i64 values can share an f64
+        let ordered = match (low.weak(), high.weak()) {
+            (OnionObject::Integer(lo), OnionObject::Integer(hi)) => lo <= hi,
+            _ => low_f <= high_f,
+        };
+        if !ordered {
+            return Err(RuntimeError::InvalidOperation(
+                "clamp_each requires low <= high".to_string().into(),
+            ));
+        }
+
+        values.weak().with_data(|values_data| match values_data {
+            OnionObject::Tuple(tuple) => {
+                let mut clamped = Vec::with_capacity(tuple.get_elements().len());
+                for element in tuple.get_elements() {
+                    let value = match (element, low.weak(), high.weak()) {
+                        (
+                            OnionObject::Integer(n),
+                            OnionObject::Integer(lo),
+                            OnionObject::Integer(hi),
+                        ) => OnionObject::Integer((*n).clamp(*lo, *hi)),
+                        _ => OnionObject::Float(
+                            numeric_value(element, "clamp_each")?.clamp(low_f, high_f),
+                        ),
+                    };
+                    clamped.push(value.stabilize());
+                }
+                Ok(OnionTuple::new_static_no_ref(&clamped))
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "clamp_each requires a tuple of numbers".to_string().into(),
+            )),
+        })
+    })
+}
+
+/// Clamp a 2D point component-wise into the box spanned by min and max
+fn clamp_point(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let point = get_attr_direct(data, "point".to_string())?;
+        let min = get_attr_direct(data, "min".to_string())?;
+        let max = get_attr_direct(data, "max".to_string())?;
+        let point = point
+            .weak()
+            .with_data(|point_data| numeric_elements(point_data, "clamp_point"))?;
+        let min = min
+            .weak()
+            .with_data(|min_data| numeric_elements(min_data, "clamp_point"))?;
+        let max = max
+            .weak()
+            .with_data(|max_data| numeric_elements(max_data, "clamp_point"))?;
+
+        if point.len() != 2 || min.len() != 2 || max.len() != 2 {
+            return Err(RuntimeError::InvalidOperation(
+                "clamp_point requires two-element tuples".to_string().into(),
+            ));
+        }
+        let mut clamped = Vec::with_capacity(2);
+        for axis in 0..2 {
+            if min[axis].is_nan() || max[axis].is_nan() || min[axis] > max[axis] {
+                return Err(RuntimeError::InvalidOperation(
+                    "clamp_point requires min <= max on every axis"
+                        .to_string()
+                        .into(),
+                ));
+            }
+            clamped.push(OnionObject::Float(point[axis].clamp(min[axis], max[axis])).stabilize());
+        }
+        Ok(OnionTuple::new_static_no_ref(&clamped))
+    })
+}
+
+/// Running aggregate of a tuple under a binary lambda, starting from `initial`
+///
+/// Shared by `math.accumulate` and `tuple.scan`; the latter also keeps `initial`
+/// as the first element of the result.
+#[derive(Clone)]
+pub struct Accumulate {
+    values_key: &'static str,
+    include_initial: bool,
+    func: Option<OnionStaticObject>,
+    values: Vec<OnionStaticObject>,
+    index: usize,
+    acc: Option<OnionStaticObject>,
+    result: Vec<OnionStaticObject>,
+}
+
+impl Accumulate {
+    /// Read the values from the `values_key` argument, optionally emitting `initial` first
+    pub fn new(values_key: &'static str, include_initial: bool) -> Self {
+        Accumulate {
+            values_key,
+            include_initial,
+            func: None,
+            values: vec![],
+            index: 0,
+            acc: None,
+            result: vec![],
+        }
+    }
+}
+
+impl Default for Accumulate {
+    fn default() -> Self {
+        Accumulate::new("values", false)
+    }
+}
+
+impl LambdaCalling for Accumulate {
+    const NAME: &'static str = "Accumulate";
+
+    fn resume(
+        &mut self,
+        argument: &OnionStaticObject,
+        result: Option<OnionStaticObject>,
+    ) -> Result<CallStep, RuntimeError> {
+        if let Some(acc) = result {
+            self.result.push(acc.clone());
+            self.acc = Some(acc);
+        }
+        if self.func.is_none() {
+            let (values, func, initial) = argument.weak().with_data(|data| {
+                let values = get_attr_direct(data, self.values_key.to_string())?;
+                let func = get_attr_direct(data, "func".to_string())?;
+                let initial = get_attr_direct(data, "initial".to_string())?;
+                Ok((values, func, initial))
+            })?;
+            self.values = values.weak().with_data(|values| match values {
+                OnionObject::Tuple(tuple) => Ok(tuple
+                    .get_elements()
+                    .iter()
+                    .map(|element| element.stabilize())
+                    .collect()),
+                _ => Err(RuntimeError::InvalidOperation(
+                    format!("Expected a tuple for '{}'", self.values_key).into(),
+                )),
+            })?;
+            if self.include_initial {
+                self.result.push(initial.clone());
+            }
+            self.acc = Some(initial);
+            self.func = Some(func);
+        }
+
+        match (&self.func, &self.acc, self.values.get(self.index)) {
+            (Some(func), Some(acc), Some(value)) => {
+                self.index += 1;
+                Ok(CallStep::Call(
+                    func.clone(),
+                    vec![acc.clone(), value.clone()],
+                ))
+            }
+            _ => Ok(CallStep::Return(OnionTuple::new_static_no_ref(
+                &self.result,
+            ))),
+        }
+    }
+
+    fn context(&self) -> serde_json::Map<String, serde_json::Value> {
+        let mut context = serde_json::Map::new();
+        context.insert("index".to_string(), self.index.into());
+        context
+    }
+}
+
+/// Linearly remap a value from [in_min, in_max] to [out_min, out_max]
+fn map_range(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let mut bounds = [0.0; 5];
+        for (bound, name) in bounds
+            .iter_mut()
+            .zip(["value", "in_min", "in_max", "out_min", "out_max"])
+        {
+            let obj = get_attr_direct(data, name.to_string())?;
+            *bound = numeric_value(obj.weak(), "map_range")?;
+        }
+        let [value, in_min, in_max, out_min, out_max] = bounds;
+        let clamp = get_attr_direct(data, "clamp".to_string())?
+            .weak()
+            .to_boolean()?;
+
+        if in_min == in_max {
+            return Err(RuntimeError::InvalidOperation(
+                "map_range requires in_min != in_max".to_string().into(),
+            ));
+        }
+        let mut mapped = out_min + (value - in_min) * (out_max - out_min) / (in_max - in_min);
+        if clamp {
+            mapped = mapped.clamp(out_min.min(out_max), out_min.max(out_max));
+        }
+        Ok(OnionObject::Float(mapped).stabilize())
+    })
+}
+
+/// Compare two numbers with relative and absolute tolerances, like Python's math.isclose
+fn is_close(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let a = get_attr_direct(data, "a".to_string())?;
+        let b = get_attr_direct(data, "b".to_string())?;
+        let rel_tol = get_attr_direct(data, "rel_tol".to_string())?;
+        let abs_tol = get_attr_direct(data, "abs_tol".to_string())?;
+        let a = numeric_value(a.weak(), "is_close")?;
+        let b = numeric_value(b.weak(), "is_close")?;
+        let rel_tol = numeric_value(rel_tol.weak(), "is_close")?;
+        let abs_tol = numeric_value(abs_tol.weak(), "is_close")?;
+
+        if rel_tol < 0.0 || abs_tol < 0.0 {
+            return Err(RuntimeError::InvalidOperation(
+                "is_close tolerances must be non-negative"
+                    .to_string()
+                    .into(),
+            ));
+        }
+        let close = if a == b {
+            true
+        } else if a.is_infinite() || b.is_infinite() {
+            false
+        } else {
+            let diff = (a - b).abs();
+            diff <= (rel_tol * b.abs()).max(rel_tol * a.abs()) || diff <= abs_tol
+        };
+        Ok(OnionObject::Boolean(close).stabilize())
+    })
+}
+
+/// Divide a by b, returning default instead of failing when b is zero
+fn safe_div(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let a = get_attr_direct(data, "a".to_string())?;
+        let b = get_attr_direct(data, "b".to_string())?;
+        let default = get_attr_direct(data, "default".to_string())?;
+
+        match (a.weak(), b.weak()) {
+            (OnionObject::Integer(_), OnionObject::Integer(0)) => Ok(default),
+            (OnionObject::Integer(a), OnionObject::Integer(b)) => match a.checked_div(*b) {
+                Some(quotient) => Ok(OnionObject::Integer(quotient).stabilize()),
+                None => Err(RuntimeError::InvalidOperation(
+                    "safe_div integer overflow".to_string().into(),
+                )),
+            },
+            (a, b) => {
+                let a = numeric_value(a, "safe_div")?;
+                let b = numeric_value(b, "safe_div")?;
+                if b == 0.0 {
+                    Ok(default)
+                } else {
+                    Ok(OnionObject::Float(a / b).stabilize())
+                }
+            }
+        }
+    })
+}
+
+/// Averages of each sliding window of a numeric tuple
+fn moving_average(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let values = get_attr_direct(data, "values".to_string())?;
+        let window = get_attr_direct(data, "window".to_string())?;
+        let values = values
+            .weak()
+            .with_data(|values_data| numeric_elements(values_data, "moving_average"))?;
+        let window = match window.weak() {
+            OnionObject::Integer(n) if *n > 0 && (*n as usize) <= values.len() => *n as usize,
+            OnionObject::Integer(_) => {
+                return Err(RuntimeError::InvalidOperation(
+                    "moving_average requires 0 < window <= len(values)"
+                        .to_string()
+                        .into(),
+                ))
+            }
+            _ => {
+                return Err(RuntimeError::InvalidOperation(
+                    "moving_average requires an integer window"
+                        .to_string()
+                        .into(),
+                ))
+            }
+        };
+
+        let averages: Vec<_> = values
+            .windows(window)
+            .map(|w| OnionObject::Float(w.iter().sum::<f64>() / window as f64).stabilize())
+            .collect();
+        Ok(OnionTuple::new_static_no_ref(&averages))
+    })
+}
+
+/// Check that the `value` argument is an integer within [min, max]
+fn checked_integer(
+    argument: &OnionStaticObject,
+    func_name: &str,
+    min: i64,
+    max: i64,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let value = get_attr_direct(data, "value".to_string())?;
+        match value.weak() {
+            OnionObject::Integer(n) if (min..=max).contains(n) => {
+                Ok(OnionObject::Integer(*n).stabilize())
+            }
+            OnionObject::Integer(n) => Err(RuntimeError::InvalidOperation(
+                format!(
+                    "{} value {} is out of range [{}, {}]",
+                    func_name, n, min, max
+                )
+                .into(),
+            )),
+            _ => Err(RuntimeError::InvalidOperation(
+                format!("{} requires an integer value", func_name).into(),
+            )),
+        }
+    })
+}
+
+/// Convert an integer to the i32 range, failing instead of truncating
+fn to_i32(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    checked_integer(argument, "to_i32", i32::MIN as i64, i32::MAX as i64)
+}
+
+/// Convert an integer to the u8 range, failing instead of truncating
+fn to_u8(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    checked_integer(argument, "to_u8", u8::MIN as i64, u8::MAX as i64)
+}
+
+/// Split a float into a `mantissa : exponent` pair, the mantissa in [0.5, 1)
+fn frexp(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let value = get_attr_direct(data, "value".to_string())?;
+        let mut x = numeric_value(value.weak(), "frexp")?;
+
+        if x == 0.0 || !x.is_finite() {
+            return Ok(OnionPair::new_static(
+                &OnionObject::Float(x).stabilize(),
+                &OnionObject::Integer(0).stabilize(),
+            ));
+        }
+        let mut adjust = 0;
+        if (x.to_bits() >> 52) & 0x7ff == 0 {
+            // subnormal: scale into the normal range first
+            x *= f64::from_bits(0x4350_0000_0000_0000); // 2^54
+            adjust = -54;
+        }
+        let bits = x.to_bits();
+        let exponent = ((bits >> 52) & 0x7ff) as i64 - 1022 + adjust;
+        let mantissa = f64::from_bits((bits & !(0x7ff << 52)) | (1022 << 52));
+        Ok(OnionPair::new_static(
+            &OnionObject::Float(mantissa).stabilize(),
+            &OnionObject::Integer(exponent).stabilize(),
+        ))
+    })
+}
+
+/// Compute mantissa * 2^exponent; a `mantissa : exponent` pair from frexp is also accepted
+fn ldexp(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let mantissa = get_attr_direct(data, "mantissa".to_string())?;
+        let (mantissa, exponent) = if matches!(mantissa.weak(), OnionObject::Pair(_)) {
+            (mantissa.weak().key_of()?, mantissa.weak().value_of()?)
+        } else {
+            (mantissa, get_attr_direct(data, "exponent".to_string())?)
+        };
+        let mut result = numeric_value(mantissa.weak(), "ldexp")?;
+        let mut exponent = match exponent.weak() {
+            OnionObject::Integer(n) => (*n).clamp(-2200, 2200) as i32,
+            _ => {
+                return Err(RuntimeError::InvalidOperation(
+                    "ldexp requires an integer exponent".to_string().into(),
+                ))
+            }
+        };
+
+        // scale in steps so intermediate powers of two stay representable
+        while exponent > 1023 && result.is_finite() && result != 0.0 {
+            result *= 2f64.powi(1023);
+            exponent -= 1023;
+        }
+        while exponent < -1022 && result.is_finite() && result != 0.0 {
+            result *= 2f64.powi(-1022);
+            exponent += 1022;
+        }
+        Ok(OnionObject::Float(result * 2f64.powi(exponent)).stabilize())
+    })
+}
+
+/// Raise value to at least floor, keeping integers as integers when both are
+fn at_least(
+    value: &OnionObject,
+    floor: &OnionObject,
+    func_name: &str,
+) -> Result<OnionStaticObject, RuntimeError> {
+    match (value, floor) {
+        (OnionObject::Integer(n), OnionObject::Integer(floor)) => {
+            Ok(OnionObject::Integer((*n).max(*floor)).stabilize())
+        }
+        _ => {
+            let value = numeric_value(value, func_name)?;
+            let floor = numeric_value(floor, func_name)?;
+            Ok(OnionObject::Float(value.max(floor)).stabilize())
+        }
+    }
+}
+
+/// Return value, or zero if it is negative
+fn clamp_nonneg(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let value = get_attr_direct(data, "value".to_string())?;
+        let zero = match value.weak() {
+            OnionObject::Float(_) => OnionObject::Float(0.0),
+            _ => OnionObject::Integer(0),
+        };
+        at_least(value.weak(), &zero, "clamp_nonneg")
+    })
+}
+
+/// Return value, or min_positive if value is smaller
+fn clamp_positive(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let value = get_attr_direct(data, "value".to_string())?;
+        let min_positive = get_attr_direct(data, "min_positive".to_string())?;
+        if numeric_value(min_positive.weak(), "clamp_positive")? <= 0.0 {
+            return Err(RuntimeError::InvalidOperation(
+                "clamp_positive requires min_positive > 0"
+                    .to_string()
+                    .into(),
+            ));
+        }
+        at_least(value.weak(), min_positive.weak(), "clamp_positive")
+    })
+}
+
+/// Number of ways to choose k items from n, failing on overflow
+fn binomial(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let n = get_attr_direct(data, "n".to_string())?;
+        let k = get_attr_direct(data, "k".to_string())?;
+
+        match (n.weak(), k.weak()) {
+            (OnionObject::Integer(n), OnionObject::Integer(k)) => {
+                if *n < 0 || *k < 0 {
+                    return Err(RuntimeError::InvalidOperation(
+                        "binomial requires non-negative n and k".to_string().into(),
+                    ));
+                }
+                if k > n {
+                    return Err(RuntimeError::InvalidOperation(
+                        format!("binomial requires k <= n, got n={}, k={}", n, k).into(),
+                    ));
+                }
+                let k = (*k).min(n - k) as i128;
+                let n = *n as i128;
+                let mut result: i128 = 1;
+                for i in 0..k {
+                    // result * (n - i) is always divisible by i + 1
+                    result = result
+                        .checked_mul(n - i)
+                        .map(|product| product / (i + 1))
+                        .filter(|value| *value <= i64::MAX as i128)
+                        .ok_or_else(|| {
+                            RuntimeError::InvalidOperation(
+                                "binomial result overflows a 64-bit integer"
+                                    .to_string()
+                                    .into(),
+                            )
+                        })?;
+                }
+                Ok(OnionObject::Integer(result as i64).stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "binomial requires integer arguments".to_string().into(),
+            )),
+        }
+    })
+}
+
+/// Count, min, max, sum and mean of a numeric tuple, computed in one pass
+fn stats(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let values = get_attr_direct(data, "values".to_string())?;
+        let values = values
+            .weak()
+            .with_data(|values_data| numeric_elements(values_data, "stats"))?;
+        if values.is_empty() {
+            return Err(RuntimeError::InvalidOperation(
+                "stats requires a non-empty tuple".to_string().into(),
+            ));
+        }
+
+        let (mut min, mut max, mut sum) = (f64::INFINITY, f64::NEG_INFINITY, 0.0);
+        for value in values.iter() {
+            min = min.min(*value);
+            max = max.max(*value);
+            sum += value;
+        }
+        let mut result = IndexMap::new();
+        result.insert(
+            "count".to_string(),
+            OnionObject::Integer(values.len() as i64).stabilize(),
+        );
+        result.insert("min".to_string(), OnionObject::Float(min).stabilize());
+        result.insert("max".to_string(), OnionObject::Float(max).stabilize());
+        result.insert("sum".to_string(), OnionObject::Float(sum).stabilize());
+        result.insert(
+            "mean".to_string(),
+            OnionObject::Float(sum / values.len() as f64).stabilize(),
+        );
+        Ok(build_named_dict(result))
+    })
+}
+
+pub fn build_module() -> OnionStaticObject {
+    let mut module = IndexMap::new();
+
+    // 数学常量
+    module.insert(
+        "PI".to_string(),
+        OnionObject::Float(std::f64::consts::PI).stabilize(),
+    );
+    module.insert(
+        "E".to_string(),
+        OnionObject::Float(std::f64::consts::E).stabilize(),
+    );
+
+    // abs 函数
+    let mut abs_params = IndexMap::new();
+    abs_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some("Number to get absolute value".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "abs".to_string(),
+        wrap_native_function(
+            &build_named_dict(abs_params),
+            None,
+            None,
+            "math::abs".to_string(),
+            &abs,
+        ),
+    ); // sin 函数
+    let mut sin_params = IndexMap::new();
+    sin_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some("Angle in radians".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "sin".to_string(),
+        wrap_native_function(
+            &build_named_dict(sin_params),
+            None,
+            None,
+            "math::sin".to_string(),
+            &sin,
+        ),
+    );
+
+    // cos 函数
+    let mut cos_params = IndexMap::new();
+    cos_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some("Angle in radians".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "cos".to_string(),
+        wrap_native_function(
+            &build_named_dict(cos_params),
+            None,
+            None,
+            "math::cos".to_string(),
+            &cos,
+        ),
+    );
+
+    // tan 函数
+    let mut tan_params = IndexMap::new();
+    tan_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some("Angle in radians".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "tan".to_string(),
+        wrap_native_function(
+            &build_named_dict(tan_params),
+            None,
+            None,
+            "math::tan".to_string(),
+            &tan,
+        ),
+    );
+
+    // log 函数
+    let mut log_params = IndexMap::new();
+    log_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some(
+            "Number to calculate natural logarithm".to_string().into(),
+        ))
+        .stabilize(),
+    );
+    module.insert(
+        "log".to_string(),
+        wrap_native_function(
+            &build_named_dict(log_params),
+            None,
+            None,
+            "math::log".to_string(),
+            &log,
+        ),
+    );
+
+    // exp 函数
+    let mut exp_params = IndexMap::new();
+    exp_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some("Exponent for e^x".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "exp".to_string(),
+        wrap_native_function(
+            &build_named_dict(exp_params),
+            None,
+            None,
+            "math::exp".to_string(),
+            &exp,
+        ),
+    );
+
+    // floor 函数
+    let mut floor_params = IndexMap::new();
+    floor_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some("Number to floor".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "floor".to_string(),
+        wrap_native_function(
+            &build_named_dict(floor_params),
+            None,
+            None,
+            "math::floor".to_string(),
+            &floor,
+        ),
+    );
+
+    // ceil 函数
+    let mut ceil_params = IndexMap::new();
+    ceil_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some("Number to ceil".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "ceil".to_string(),
+        wrap_native_function(
+            &build_named_dict(ceil_params),
+            None,
+            None,
+            "math::ceil".to_string(),
+            &ceil,
+        ),
+    );
+
+    // round 函数
+    let mut round_params = IndexMap::new();
+    round_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some("Number to round".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "round".to_string(),
+        wrap_native_function(
+            &build_named_dict(round_params),
+            None,
+            None,
+            "math::round".to_string(),
+            &round,
+        ),
+    );
+
+    // asin 函数
+    let mut asin_params = IndexMap::new();
+    asin_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some("Value between -1 and 1".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "asin".to_string(),
+        wrap_native_function(
+            &build_named_dict(asin_params),
+            None,
+            None,
+            "math::asin".to_string(),
+            &asin,
+        ),
+    );
+
+    // acos 函数
+    let mut acos_params = IndexMap::new();
+    acos_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some("Value between -1 and 1".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "acos".to_string(),
+        wrap_native_function(
+            &build_named_dict(acos_params),
+            None,
+            None,
+            "math::acos".to_string(),
+            &acos,
+        ),
+    );
+
+    // atan 函数
+    let mut atan_params = IndexMap::new();
+    atan_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some("Value for arctangent".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "atan".to_string(),
+        wrap_native_function(
+            &build_named_dict(atan_params),
+            None,
+            None,
+            "math::atan".to_string(),
+            &atan,
+        ),
+    );
+
+    // sqrt 函数
+    let mut sqrt_params = IndexMap::new();
+    sqrt_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some("Number to calculate square root".to_string().into()))
+            .stabilize(),
+    );
+    module.insert(
+        "sqrt".to_string(),
+        wrap_native_function(
+            &build_named_dict(sqrt_params),
+            None,
+            None,
+            "math::sqrt".to_string(),
+            &sqrt,
+        ),
+    );
+
+    // pow 函数
+    let mut pow_params = IndexMap::new();
+    pow_params.insert(
+        "base".to_string(),
+        OnionObject::Undefined(Some("Base number".to_string().into())).stabilize(),
+    );
+    pow_params.insert(
+        "exponent".to_string(),
+        OnionObject::Undefined(Some("Exponent (power)".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "pow".to_string(),
+        wrap_native_function(
+            &build_named_dict(pow_params),
+            None,
+            None,
+            "math::pow".to_string(),
+            &pow,
+        ),
+    );
+
+    // exp 函数
+    let mut exp_params = IndexMap::new();
+    exp_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some("Number to calculate exponent".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "exp".to_string(),
+        wrap_native_function(
+            &build_named_dict(exp_params),
+            None,
+            None,
+            "math::exp".to_string(),
+            &exp,
+        ),
+    );
+
+    // floor 函数
+    let mut floor_params = IndexMap::new();
+    floor_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some("Number to round down".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "floor".to_string(),
+        wrap_native_function(
+            &build_named_dict(floor_params),
+            None,
+            None,
+            "math::floor".to_string(),
+            &floor,
+        ),
+    );
+
+    // ceil 函数
+    let mut ceil_params = IndexMap::new();
+    ceil_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some("Number to round up".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "ceil".to_string(),
+        wrap_native_function(
+            &build_named_dict(ceil_params),
+            None,
+            None,
+            "math::ceil".to_string(),
+            &ceil,
         ),
     );
 
@@ -478,222 +1739,635 @@ pub fn build_module() -> OnionStaticObject {
         OnionObject::Undefined(Some("Number to round".to_string().into())).stabilize(),
     );
     module.insert(
-        "round".to_string(),
+        "round".to_string(),
+        wrap_native_function(
+            &build_named_dict(round_params),
+            None,
+            None,
+            "math::round".to_string(),
+            &round,
+        ),
+    );
+
+    // asin 函数
+    let mut asin_params = IndexMap::new();
+    asin_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some("Value in radians".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "asin".to_string(),
+        wrap_native_function(
+            &build_named_dict(asin_params),
+            None,
+            None,
+            "math::asin".to_string(),
+            &asin,
+        ),
+    );
+
+    // acos 函数
+    let mut acos_params = IndexMap::new();
+    acos_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some("Value in radians".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "acos".to_string(),
+        wrap_native_function(
+            &build_named_dict(acos_params),
+            None,
+            None,
+            "math::acos".to_string(),
+            &acos,
+        ),
+    );
+
+    // atan 函数
+    let mut atan_params = IndexMap::new();
+    atan_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some("Value in radians".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "atan".to_string(),
+        wrap_native_function(
+            &build_named_dict(atan_params),
+            None,
+            None,
+            "math::atan".to_string(),
+            &atan,
+        ),
+    );
+
+    // dot 函数
+    let mut dot_params = IndexMap::new();
+    dot_params.insert(
+        "a".to_string(),
+        OnionObject::Undefined(Some("First vector tuple".to_string().into())).stabilize(),
+    );
+    dot_params.insert(
+        "b".to_string(),
+        OnionObject::Undefined(Some("Second vector tuple".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "dot".to_string(),
         wrap_native_function(
-            &build_named_dict(round_params),
+            &build_named_dict(dot_params),
             None,
             None,
-            "math::round".to_string(),
-            &round,
+            "math::dot".to_string(),
+            &dot,
         ),
     );
 
-    // asin 函数
-    let mut asin_params = IndexMap::new();
-    asin_params.insert(
-        "value".to_string(),
-        OnionObject::Undefined(Some("Value between -1 and 1".to_string().into())).stabilize(),
+    // norm 函数
+    let mut norm_params = IndexMap::new();
+    norm_params.insert(
+        "vector".to_string(),
+        OnionObject::Undefined(Some("Vector tuple to measure".to_string().into())).stabilize(),
     );
     module.insert(
-        "asin".to_string(),
+        "norm".to_string(),
         wrap_native_function(
-            &build_named_dict(asin_params),
+            &build_named_dict(norm_params),
             None,
             None,
-            "math::asin".to_string(),
-            &asin,
+            "math::norm".to_string(),
+            &norm,
         ),
     );
 
-    // acos 函数
-    let mut acos_params = IndexMap::new();
-    acos_params.insert(
+    // clamp_angle 函数
+    let mut clamp_angle_params = IndexMap::new();
+    clamp_angle_params.insert(
         "value".to_string(),
-        OnionObject::Undefined(Some("Value between -1 and 1".to_string().into())).stabilize(),
+        OnionObject::Undefined(Some("Angle in degrees".to_string().into())).stabilize(),
     );
     module.insert(
-        "acos".to_string(),
+        "clamp_angle".to_string(),
         wrap_native_function(
-            &build_named_dict(acos_params),
+            &build_named_dict(clamp_angle_params),
             None,
             None,
-            "math::acos".to_string(),
-            &acos,
+            "math::clamp_angle".to_string(),
+            &clamp_angle,
         ),
     );
 
-    // atan 函数
-    let mut atan_params = IndexMap::new();
-    atan_params.insert(
+    // angle_diff 函数
+    let mut angle_diff_params = IndexMap::new();
+    angle_diff_params.insert(
+        "a".to_string(),
+        OnionObject::Undefined(Some("Starting angle in degrees".to_string().into())).stabilize(),
+    );
+    angle_diff_params.insert(
+        "b".to_string(),
+        OnionObject::Undefined(Some("Target angle in degrees".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "angle_diff".to_string(),
+        wrap_native_function(
+            &build_named_dict(angle_diff_params),
+            None,
+            None,
+            "math::angle_diff".to_string(),
+            &angle_diff,
+        ),
+    );
+
+    // solve_quadratic 函数
+    let mut solve_quadratic_params = IndexMap::new();
+    solve_quadratic_params.insert(
+        "a".to_string(),
+        OnionObject::Undefined(Some("Coefficient of x^2".to_string().into())).stabilize(),
+    );
+    solve_quadratic_params.insert(
+        "b".to_string(),
+        OnionObject::Undefined(Some("Coefficient of x".to_string().into())).stabilize(),
+    );
+    solve_quadratic_params.insert(
+        "c".to_string(),
+        OnionObject::Undefined(Some("Constant term".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "solve_quadratic".to_string(),
+        wrap_native_function(
+            &build_named_dict(solve_quadratic_params),
+            None,
+            None,
+            "math::solve_quadratic".to_string(),
+            &solve_quadratic,
+        ),
+    );
+
+    // interp_table 函数
+    let mut interp_table_params = IndexMap::new();
+    interp_table_params.insert(
+        "x".to_string(),
+        OnionObject::Undefined(Some("Point to interpolate at".to_string().into())).stabilize(),
+    );
+    interp_table_params.insert(
+        "xs".to_string(),
+        OnionObject::Undefined(Some("Ascending tuple of table inputs".to_string().into()))
+            .stabilize(),
+    );
+    interp_table_params.insert(
+        "ys".to_string(),
+        OnionObject::Undefined(Some("Tuple of table outputs".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "interp_table".to_string(),
+        wrap_native_function(
+            &build_named_dict(interp_table_params),
+            None,
+            None,
+            "math::interp_table".to_string(),
+            &interp_table,
+        ),
+    );
+
+    // snap 函数
+    let mut snap_params = IndexMap::new();
+    snap_params.insert(
         "value".to_string(),
-        OnionObject::Undefined(Some("Value for arctangent".to_string().into())).stabilize(),
+        OnionObject::Undefined(Some("Value to snap".to_string().into())).stabilize(),
     );
+    snap_params.insert("epsilon".to_string(), OnionObject::Float(1e-9).stabilize());
     module.insert(
-        "atan".to_string(),
+        "snap".to_string(),
         wrap_native_function(
-            &build_named_dict(atan_params),
+            &build_named_dict(snap_params),
             None,
             None,
-            "math::atan".to_string(),
-            &atan,
+            "math::snap".to_string(),
+            &snap,
         ),
     );
 
-    // sqrt 函数
-    let mut sqrt_params = IndexMap::new();
-    sqrt_params.insert(
+    // wrap 函数
+    let mut wrap_params = IndexMap::new();
+    wrap_params.insert(
         "value".to_string(),
-        OnionObject::Undefined(Some("Number to calculate square root".to_string().into()))
+        OnionObject::Undefined(Some("Value to wrap".to_string().into())).stabilize(),
+    );
+    wrap_params.insert(
+        "min".to_string(),
+        OnionObject::Undefined(Some("Inclusive lower bound".to_string().into())).stabilize(),
+    );
+    wrap_params.insert(
+        "max".to_string(),
+        OnionObject::Undefined(Some("Exclusive upper bound".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "wrap".to_string(),
+        wrap_native_function(
+            &build_named_dict(wrap_params),
+            None,
+            None,
+            "math::wrap".to_string(),
+            &wrap,
+        ),
+    );
+
+    // running_mean 函数
+    module.insert(
+        "running_mean".to_string(),
+        wrap_native_function(
+            &onion_tuple!(),
+            None,
+            None,
+            "math::running_mean".to_string(),
+            &running_mean,
+        ),
+    );
+
+    // clamp_index 函数
+    let mut clamp_index_params = IndexMap::new();
+    clamp_index_params.insert(
+        "index".to_string(),
+        OnionObject::Undefined(Some(
+            "Index to resolve, negative values count from the end"
+                .to_string()
+                .into(),
+        ))
+        .stabilize(),
+    );
+    clamp_index_params.insert(
+        "length".to_string(),
+        OnionObject::Undefined(Some("Length of the indexed container".to_string().into()))
             .stabilize(),
     );
     module.insert(
-        "sqrt".to_string(),
+        "clamp_index".to_string(),
         wrap_native_function(
-            &build_named_dict(sqrt_params),
+            &build_named_dict(clamp_index_params),
             None,
             None,
-            "math::sqrt".to_string(),
-            &sqrt,
+            "math::clamp_index".to_string(),
+            &clamp_index,
         ),
     );
 
-    // pow 函数
-    let mut pow_params = IndexMap::new();
-    pow_params.insert(
-        "base".to_string(),
-        OnionObject::Undefined(Some("Base number".to_string().into())).stabilize(),
+    // poly_eval 函数
+    let mut poly_eval_params = IndexMap::new();
+    poly_eval_params.insert(
+        "coeffs".to_string(),
+        OnionObject::Undefined(Some(
+            "Tuple of coefficients, highest degree first"
+                .to_string()
+                .into(),
+        ))
+        .stabilize(),
     );
-    pow_params.insert(
-        "exponent".to_string(),
-        OnionObject::Undefined(Some("Exponent (power)".to_string().into())).stabilize(),
+    poly_eval_params.insert(
+        "x".to_string(),
+        OnionObject::Undefined(Some("Point to evaluate at".to_string().into())).stabilize(),
     );
     module.insert(
-        "pow".to_string(),
+        "poly_eval".to_string(),
         wrap_native_function(
-            &build_named_dict(pow_params),
+            &build_named_dict(poly_eval_params),
             None,
             None,
-            "math::pow".to_string(),
-            &pow,
+            "math::poly_eval".to_string(),
+            &poly_eval,
         ),
     );
 
-    // exp 函数
-    let mut exp_params = IndexMap::new();
-    exp_params.insert(
-        "value".to_string(),
-        OnionObject::Undefined(Some("Number to calculate exponent".to_string().into())).stabilize(),
+    // clamp_each 函数
+    let mut clamp_each_params = IndexMap::new();
+    clamp_each_params.insert(
+        "values".to_string(),
+        OnionObject::Undefined(Some("Tuple of numbers to clamp".to_string().into())).stabilize(),
+    );
+    clamp_each_params.insert(
+        "low".to_string(),
+        OnionObject::Undefined(Some("Lower bound".to_string().into())).stabilize(),
+    );
+    clamp_each_params.insert(
+        "high".to_string(),
+        OnionObject::Undefined(Some("Upper bound".to_string().into())).stabilize(),
     );
     module.insert(
-        "exp".to_string(),
+        "clamp_each".to_string(),
         wrap_native_function(
-            &build_named_dict(exp_params),
+            &build_named_dict(clamp_each_params),
             None,
             None,
-            "math::exp".to_string(),
-            &exp,
+            "math::clamp_each".to_string(),
+            &clamp_each,
         ),
     );
 
-    // floor 函数
-    let mut floor_params = IndexMap::new();
-    floor_params.insert(
+    // clamp_point 函数
+    let mut clamp_point_params = IndexMap::new();
+    clamp_point_params.insert(
+        "point".to_string(),
+        OnionObject::Undefined(Some("Two-element tuple (x, y)".to_string().into())).stabilize(),
+    );
+    clamp_point_params.insert(
+        "min".to_string(),
+        OnionObject::Undefined(Some("Two-element tuple of lower bounds".to_string().into()))
+            .stabilize(),
+    );
+    clamp_point_params.insert(
+        "max".to_string(),
+        OnionObject::Undefined(Some("Two-element tuple of upper bounds".to_string().into()))
+            .stabilize(),
+    );
+    module.insert(
+        "clamp_point".to_string(),
+        wrap_native_function(
+            &build_named_dict(clamp_point_params),
+            None,
+            None,
+            "math::clamp_point".to_string(),
+            &clamp_point,
+        ),
+    );
+
+    // accumulate 函数
+    let mut accumulate_params = IndexMap::new();
+    accumulate_params.insert(
+        "values".to_string(),
+        OnionObject::Undefined(Some("Tuple of values to fold".to_string().into())).stabilize(),
+    );
+    accumulate_params.insert(
+        "func".to_string(),
+        OnionObject::Undefined(Some(
+            "Binary function (acc, value) -> acc".to_string().into(),
+        ))
+        .stabilize(),
+    );
+    accumulate_params.insert(
+        "initial".to_string(),
+        OnionObject::Undefined(Some("Initial accumulator".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "accumulate".to_string(),
+        OnionLambdaDefinition::new_static(
+            &build_named_dict(accumulate_params),
+            LambdaBody::NativeFunction(Box::new(LambdaCaller::new(Accumulate::default()))),
+            None,
+            None,
+            "math::accumulate".to_string(),
+        ),
+    );
+
+    // map_range 函数
+    let mut map_range_params = IndexMap::new();
+    map_range_params.insert(
         "value".to_string(),
-        OnionObject::Undefined(Some("Number to round down".to_string().into())).stabilize(),
+        OnionObject::Undefined(Some("Value to remap".to_string().into())).stabilize(),
+    );
+    map_range_params.insert(
+        "in_min".to_string(),
+        OnionObject::Undefined(Some("Lower bound of the input range".to_string().into()))
+            .stabilize(),
+    );
+    map_range_params.insert(
+        "in_max".to_string(),
+        OnionObject::Undefined(Some("Upper bound of the input range".to_string().into()))
+            .stabilize(),
+    );
+    map_range_params.insert(
+        "out_min".to_string(),
+        OnionObject::Undefined(Some("Lower bound of the output range".to_string().into()))
+            .stabilize(),
     );
+    map_range_params.insert(
+        "out_max".to_string(),
+        OnionObject::Undefined(Some("Upper bound of the output range".to_string().into()))
+            .stabilize(),
+    );
+    map_range_params.insert("clamp".to_string(), OnionObject::Boolean(false).stabilize());
     module.insert(
-        "floor".to_string(),
+        "map_range".to_string(),
         wrap_native_function(
-            &build_named_dict(floor_params),
+            &build_named_dict(map_range_params),
             None,
             None,
-            "math::floor".to_string(),
-            &floor,
+            "math::map_range".to_string(),
+            &map_range,
         ),
     );
 
-    // ceil 函数
-    let mut ceil_params = IndexMap::new();
-    ceil_params.insert(
+    // is_close 函数
+    let mut is_close_params = IndexMap::new();
+    is_close_params.insert(
+        "a".to_string(),
+        OnionObject::Undefined(Some("First value".to_string().into())).stabilize(),
+    );
+    is_close_params.insert(
+        "b".to_string(),
+        OnionObject::Undefined(Some("Second value".to_string().into())).stabilize(),
+    );
+    is_close_params.insert("rel_tol".to_string(), OnionObject::Float(1e-9).stabilize());
+    is_close_params.insert("abs_tol".to_string(), OnionObject::Float(0.0).stabilize());
+    module.insert(
+        "is_close".to_string(),
+        wrap_native_function(
+            &build_named_dict(is_close_params),
+            None,
+            None,
+            "math::is_close".to_string(),
+            &is_close,
+        ),
+    );
+
+    // safe_div 函数
+    let mut safe_div_params = IndexMap::new();
+    safe_div_params.insert(
+        "a".to_string(),
+        OnionObject::Undefined(Some("Dividend".to_string().into())).stabilize(),
+    );
+    safe_div_params.insert(
+        "b".to_string(),
+        OnionObject::Undefined(Some("Divisor".to_string().into())).stabilize(),
+    );
+    safe_div_params.insert("default".to_string(), OnionObject::Null.stabilize());
+    module.insert(
+        "safe_div".to_string(),
+        wrap_native_function(
+            &build_named_dict(safe_div_params),
+            None,
+            None,
+            "math::safe_div".to_string(),
+            &safe_div,
+        ),
+    );
+
+    // moving_average 函数
+    let mut moving_average_params = IndexMap::new();
+    moving_average_params.insert(
+        "values".to_string(),
+        OnionObject::Undefined(Some("Tuple of numbers".to_string().into())).stabilize(),
+    );
+    moving_average_params.insert(
+        "window".to_string(),
+        OnionObject::Undefined(Some("Window length".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "moving_average".to_string(),
+        wrap_native_function(
+            &build_named_dict(moving_average_params),
+            None,
+            None,
+            "math::moving_average".to_string(),
+            &moving_average,
+        ),
+    );
+
+    // to_i32 函数
+    let mut to_i32_params = IndexMap::new();
+    to_i32_params.insert(
         "value".to_string(),
-        OnionObject::Undefined(Some("Number to round up".to_string().into())).stabilize(),
+        OnionObject::Undefined(Some("Integer to convert".to_string().into())).stabilize(),
     );
     module.insert(
-        "ceil".to_string(),
+        "to_i32".to_string(),
         wrap_native_function(
-            &build_named_dict(ceil_params),
+            &build_named_dict(to_i32_params),
             None,
             None,
-            "math::ceil".to_string(),
-            &ceil,
+            "math::to_i32".to_string(),
+            &to_i32,
         ),
     );
 
-    // round 函数
-    let mut round_params = IndexMap::new();
-    round_params.insert(
+    // to_u8 函数
+    let mut to_u8_params = IndexMap::new();
+    to_u8_params.insert(
         "value".to_string(),
-        OnionObject::Undefined(Some("Number to round".to_string().into())).stabilize(),
+        OnionObject::Undefined(Some("Integer to convert".to_string().into())).stabilize(),
     );
     module.insert(
-        "round".to_string(),
+        "to_u8".to_string(),
         wrap_native_function(
-            &build_named_dict(round_params),
+            &build_named_dict(to_u8_params),
             None,
             None,
-            "math::round".to_string(),
-            &round,
+            "math::to_u8".to_string(),
+            &to_u8,
         ),
     );
 
-    // asin 函数
-    let mut asin_params = IndexMap::new();
-    asin_params.insert(
+    // frexp 函数
+    let mut frexp_params = IndexMap::new();
+    frexp_params.insert(
         "value".to_string(),
-        OnionObject::Undefined(Some("Value in radians".to_string().into())).stabilize(),
+        OnionObject::Undefined(Some("Number to decompose".to_string().into())).stabilize(),
     );
     module.insert(
-        "asin".to_string(),
+        "frexp".to_string(),
         wrap_native_function(
-            &build_named_dict(asin_params),
+            &build_named_dict(frexp_params),
             None,
             None,
-            "math::asin".to_string(),
-            &asin,
+            "math::frexp".to_string(),
+            &frexp,
         ),
     );
 
-    // acos 函数
-    let mut acos_params = IndexMap::new();
-    acos_params.insert(
+    // ldexp 函数
+    let mut ldexp_params = IndexMap::new();
+    ldexp_params.insert(
+        "mantissa".to_string(),
+        OnionObject::Undefined(Some(
+            "Mantissa to scale, or a mantissa : exponent pair"
+                .to_string()
+                .into(),
+        ))
+        .stabilize(),
+    );
+    ldexp_params.insert("exponent".to_string(), OnionObject::Null.stabilize());
+    module.insert(
+        "ldexp".to_string(),
+        wrap_native_function(
+            &build_named_dict(ldexp_params),
+            None,
+            None,
+            "math::ldexp".to_string(),
+            &ldexp,
+        ),
+    );
+
+    // clamp_nonneg 函数
+    let mut clamp_nonneg_params = IndexMap::new();
+    clamp_nonneg_params.insert(
         "value".to_string(),
-        OnionObject::Undefined(Some("Value in radians".to_string().into())).stabilize(),
+        OnionObject::Undefined(Some("Number to clamp".to_string().into())).stabilize(),
     );
     module.insert(
-        "acos".to_string(),
+        "clamp_nonneg".to_string(),
         wrap_native_function(
-            &build_named_dict(acos_params),
+            &build_named_dict(clamp_nonneg_params),
             None,
             None,
-            "math::acos".to_string(),
-            &acos,
+            "math::clamp_nonneg".to_string(),
+            &clamp_nonneg,
         ),
     );
 
-    // atan 函数
-    let mut atan_params = IndexMap::new();
-    atan_params.insert(
+    // clamp_positive 函数
+    let mut clamp_positive_params = IndexMap::new();
+    clamp_positive_params.insert(
         "value".to_string(),
-        OnionObject::Undefined(Some("Value in radians".to_string().into())).stabilize(),
+        OnionObject::Undefined(Some("Number to clamp".to_string().into())).stabilize(),
+    );
+    clamp_positive_params.insert(
+        "min_positive".to_string(),
+        OnionObject::Undefined(Some("Smallest allowed positive value".to_string().into()))
+            .stabilize(),
     );
     module.insert(
-        "atan".to_string(),
+        "clamp_positive".to_string(),
         wrap_native_function(
-            &build_named_dict(atan_params),
+            &build_named_dict(clamp_positive_params),
             None,
             None,
-            "math::atan".to_string(),
-            &atan,
+            "math::clamp_positive".to_string(),
+            &clamp_positive,
+        ),
+    );
+
+    // binomial 函数
+    let mut binomial_params = IndexMap::new();
+    binomial_params.insert(
+        "n".to_string(),
+        OnionObject::Undefined(Some("Size of the set".to_string().into())).stabilize(),
+    );
+    binomial_params.insert(
+        "k".to_string(),
+        OnionObject::Undefined(Some("Number of items to choose".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "binomial".to_string(),
+        wrap_native_function(
+            &build_named_dict(binomial_params),
+            None,
+            None,
+            "math::binomial".to_string(),
+            &binomial,
+        ),
+    );
+
+    // stats 函数
+    let mut stats_params = IndexMap::new();
+    stats_params.insert(
+        "values".to_string(),
+        OnionObject::Undefined(Some("Tuple of numbers to summarize".to_string().into()))
+            .stabilize(),
+    );
+    module.insert(
+        "stats".to_string(),
+        wrap_native_function(
+            &build_named_dict(stats_params),
+            None,
+            None,
+            "math::stats".to_string(),
+            &stats,
         ),
     );
 
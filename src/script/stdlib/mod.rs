@@ -3,7 +3,10 @@ use onion_vm::{
     lambda::runnable::{Runnable, RuntimeError, StepResult},
     onion_tuple,
     types::{
-        lambda::definition::{LambdaBody, OnionLambdaDefinition},
+        lambda::{
+            definition::{LambdaBody, OnionLambdaDefinition},
+            launcher::OnionLambdaRunnableLauncher,
+        },
         named::OnionNamed,
         object::{OnionObject, OnionObjectCell, OnionStaticObject},
         tuple::OnionTuple,
@@ -11,13 +14,21 @@ use onion_vm::{
     unwrap_step_result, GC,
 };
 
+mod bigint;
 mod bytes;
+mod collections;
+mod json;
 mod math;
 mod string;
 mod time;
 mod tuple;
 mod types;
 
+pub use types::pretty_string;
+
+/// Largest string or tuple a stdlib function will build from a script-supplied size
+pub const MAX_GENERATED_LEN: usize = 16 * 1024 * 1024;
+
 pub fn build_named_dict(dict: IndexMap<String, OnionStaticObject>) -> OnionStaticObject {
     let mut pairs = vec![];
     for (key, value) in dict {
@@ -33,6 +44,146 @@ pub fn get_attr_direct(obj: &OnionObject, key: String) -> Result<OnionStaticObje
     obj.with_attribute(&OnionObject::String(key.into()), &|obj| Ok(obj.stabilize()))
 }
 
+/// Launch `function` with positional `arguments` as a nested runnable.
+///
+/// The scheduler runs the returned runnable and hands its result back to the
+/// caller through `Runnable::receive` as a `StepResult::Return`.
+pub fn call_lambda(function: &OnionStaticObject, arguments: Vec<&OnionStaticObject>) -> StepResult {
+    match OnionLambdaRunnableLauncher::new_static(function, &OnionTuple::new_static(arguments), Ok)
+    {
+        Ok(runnable) => StepResult::NewRunnable(Box::new(runnable)),
+        Err(e) => StepResult::Error(e),
+    }
+}
+
+/// Next move of a `LambdaCalling` body
+pub enum CallStep {
+    /// Call `func` with positional arguments; its result is passed to the next `resume`
+    Call(OnionStaticObject, Vec<OnionStaticObject>),
+    /// Finish with this value
+    Return(OnionStaticObject),
+}
+
+/// Native lambda body that calls back into script lambdas.
+///
+/// `LambdaCaller` runs it: the bound argument and the result of every requested
+/// call are fed back through `resume` until it returns a value.
+pub trait LambdaCalling: Clone + Send + Sync + 'static {
+    /// Name reported in errors and `format_context`
+    const NAME: &'static str;
+
+    /// Advance with the bound argument and the result of the previous `CallStep::Call`
+    fn resume(
+        &mut self,
+        argument: &OnionStaticObject,
+        result: Option<OnionStaticObject>,
+    ) -> Result<CallStep, RuntimeError>;
+
+    /// Extra fields for `format_context`
+    fn context(&self) -> serde_json::Map<String, serde_json::Value> {
+        serde_json::Map::new()
+    }
+}
+
+/// Runnable driving a `LambdaCalling` body
+#[derive(Clone)]
+pub struct LambdaCaller<T: LambdaCalling> {
+    argument: OnionStaticObject,
+    awaiting: bool,
+    result: Option<OnionStaticObject>,
+    body: T,
+}
+
+impl<T: LambdaCalling> LambdaCaller<T> {
+    pub fn new(body: T) -> Self {
+        LambdaCaller {
+            argument: onion_tuple!(),
+            awaiting: false,
+            result: None,
+            body,
+        }
+    }
+}
+
+impl<T: LambdaCalling> Runnable for LambdaCaller<T> {
+    fn step(&mut self, _gc: &mut GC<OnionObjectCell>) -> StepResult {
+        let result = self.result.take();
+        match unwrap_step_result!(self.body.resume(&self.argument, result)) {
+            CallStep::Call(func, arguments) => {
+                self.awaiting = true;
+                call_lambda(&func, arguments.iter().collect())
+            }
+            CallStep::Return(value) => StepResult::Return(value.into()),
+        }
+    }
+
+    fn receive(
+        &mut self,
+        step_result: &StepResult,
+        _gc: &mut GC<OnionObjectCell>,
+    ) -> Result<(), RuntimeError> {
+        match step_result {
+            StepResult::Return(result) => {
+                if self.awaiting {
+                    self.awaiting = false;
+                    self.result = Some(result.as_ref().clone());
+                } else {
+                    self.argument = result.as_ref().clone();
+                }
+                Ok(())
+            }
+            StepResult::SetSelfObject(_) => Ok(()),
+            _ => Err(RuntimeError::DetailedError(
+                format!("{} received unexpected step result", T::NAME).into(),
+            )),
+        }
+    }
+
+    fn copy(&self) -> Box<dyn Runnable> {
+        Box::new(self.clone())
+    }
+
+    fn format_context(&self) -> Result<serde_json::Value, RuntimeError> {
+        let mut context = serde_json::Map::new();
+        context.insert("type".to_string(), T::NAME.into());
+        context.insert("argument".to_string(), self.argument.to_string().into());
+        context.extend(self.body.context());
+        Ok(serde_json::Value::Object(context))
+    }
+}
+
+/// Resolve the Custom object of type `T` behind a native method's self object.
+///
+/// The self object is either the Custom itself or a dict exposing it as `handle`.
+pub fn with_self_custom<T, R, F>(
+    self_object: Option<&OnionStaticObject>,
+    f: F,
+) -> Result<R, RuntimeError>
+where
+    T: 'static,
+    F: Fn(&T) -> Result<R, RuntimeError>,
+{
+    let self_object = self_object.ok_or_else(|| {
+        RuntimeError::InvalidOperation("method called without self object".to_string().into())
+    })?;
+    let handle = self_object.weak().with_data(|data| match data {
+        OnionObject::Custom(_) => Ok(self_object.clone()),
+        _ => get_attr_direct(data, "handle".to_string()),
+    })?;
+    handle.weak().with_data(|data| {
+        let target = match data {
+            OnionObject::Custom(custom) => custom.as_any().downcast_ref::<T>(),
+            _ => None,
+        };
+        match target {
+            Some(target) => f(target),
+            None => Err(RuntimeError::InvalidOperation(
+                format!("self object is not a {}", std::any::type_name::<T>()).into(),
+            )),
+        }
+    })
+}
+
 pub struct NativeFunctionGenerator<F>
 where
     F: Fn(&OnionStaticObject, &mut GC<OnionObjectCell>) -> Result<OnionStaticObject, RuntimeError>
@@ -337,7 +488,6 @@ where
     }
 }
 
-#[allow(dead_code)]
 pub fn wrap_native_method_function<F>(
     params: &OnionStaticObject,
     capture: Option<&OnionStaticObject>,
@@ -369,11 +519,32 @@ where
 }
 
 pub fn build_module() -> OnionStaticObject {
+    build_named_dict(builtin_modules())
+}
+
+/// Build the stdlib with host-provided entries placed alongside the built-in modules
+pub fn build_module_with(
+    extra: &IndexMap<String, OnionStaticObject>,
+) -> Result<OnionStaticObject, String> {
+    let mut module = builtin_modules();
+    for (name, value) in extra {
+        if module.contains_key(name) {
+            return Err(format!("'{}' would shadow a built-in stdlib module", name));
+        }
+        module.insert(name.clone(), value.clone());
+    }
+    Ok(build_named_dict(module))
+}
+
+fn builtin_modules() -> IndexMap<String, OnionStaticObject> {
     let mut module = IndexMap::new();
     module.insert("bytes".to_string(), bytes::build_module());
     module.insert("types".to_string(), types::build_module());
     module.insert("math".to_string(), math::build_module());
     module.insert("string".to_string(), string::build_module());
     module.insert("time".to_string(), time::build_module());
-    build_named_dict(module)
+    module.insert("bigint".to_string(), bigint::build_module());
+    module.insert("collections".to_string(), collections::build_module());
+    module.insert("json".to_string(), json::build_module());
+    module
 }
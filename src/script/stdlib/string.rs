@@ -5,7 +5,7 @@ use onion_vm::{
     GC,
 };
 
-use super::{build_named_dict, get_attr_direct, wrap_native_function};
+use super::{build_named_dict, get_attr_direct, wrap_native_function, MAX_GENERATED_LEN};
 
 fn length(
     argument: &OnionStaticObject,
@@ -448,359 +448,1917 @@ fn reverse(
     })
 }
 
+/// Compute the Levenshtein edit distance between two character sequences
+fn levenshtein_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            current[j + 1] = (previous[j + 1] + 1)
+                .min(current[j] + 1)
+                .min(previous[j] + cost);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// Normalized similarity of two strings in [0, 1]
+fn similarity_ratio(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let a = get_attr_direct(data, "a".to_string())?;
+        let b = get_attr_direct(data, "b".to_string())?;
+
+        a.weak().with_data(|a_data| {
+            b.weak().with_data(|b_data| match (a_data, b_data) {
+                (OnionObject::String(s1), OnionObject::String(s2)) => {
+                    let chars_a: Vec<char> = s1.chars().collect();
+                    let chars_b: Vec<char> = s2.chars().collect();
+                    let max_len = std::cmp::max(chars_a.len(), chars_b.len());
+                    if max_len == 0 {
+                        return Ok(OnionObject::Float(1.0).stabilize());
+                    }
+                    let distance = levenshtein_distance(&chars_a, &chars_b);
+                    Ok(OnionObject::Float(1.0 - distance as f64 / max_len as f64).stabilize())
+                }
+                _ => Err(RuntimeError::InvalidOperation(
+                    "similarity_ratio requires string arguments"
+                        .to_string()
+                        .into(),
+                )),
+            })
+        })
+    })
+}
+
+/// Escape non-printable and non-ASCII bytes as \xNN sequences
+fn hex_escape(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let string = get_attr_direct(data, "string".to_string())?;
+        string.weak().with_data(|string_data| match string_data {
+            OnionObject::String(s) => {
+                let mut result = String::with_capacity(s.len());
+                for byte in s.bytes() {
+                    // 反斜杠本身也需要转义，保证可以无歧义地还原
+                    if (0x20..0x7f).contains(&byte) && byte != b'\\' {
+                        result.push(byte as char);
+                    } else {
+                        result.push_str(&format!("\\x{:02x}", byte));
+                    }
+                }
+                Ok(OnionObject::String(result.into()).stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "hex_escape requires string".to_string().into(),
+            )),
+        })
+    })
+}
+
+/// Decode \xNN sequences produced by hex_escape
+fn hex_unescape(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let string = get_attr_direct(data, "string".to_string())?;
+        string.weak().with_data(|string_data| match string_data {
+            OnionObject::String(s) => {
+                let bytes = s.as_bytes();
+                let mut result = Vec::with_capacity(bytes.len());
+                let mut i = 0;
+                while i < bytes.len() {
+                    if bytes[i] == b'\\' && i + 1 < bytes.len() && bytes[i + 1] == b'x' {
+                        let byte = s
+                            .get(i + 2..i + 4)
+                            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                            .ok_or_else(|| {
+                                RuntimeError::InvalidOperation(
+                                    format!("Invalid \\x escape at offset {}", i).into(),
+                                )
+                            })?;
+                        result.push(byte);
+                        i += 4;
+                    } else {
+                        result.push(bytes[i]);
+                        i += 1;
+                    }
+                }
+                match String::from_utf8(result) {
+                    Ok(decoded) => Ok(OnionObject::String(decoded.into()).stabilize()),
+                    Err(e) => Err(RuntimeError::InvalidOperation(
+                        format!("Unescaped bytes are not valid UTF-8: {}", e).into(),
+                    )),
+                }
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "hex_unescape requires string".to_string().into(),
+            )),
+        })
+    })
+}
+
+/// Largest LCS table `diff` will allocate, counted in cells
+const MAX_DIFF_CELLS: usize = 4 * 1024 * 1024;
+
+/// Line diff of two strings based on the longest common subsequence of lines
+fn diff(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    use onion_vm::types::tuple::OnionTuple;
+
+    argument.weak().with_data(|data| {
+        let a = get_attr_direct(data, "a".to_string())?;
+        let b = get_attr_direct(data, "b".to_string())?;
+
+        a.weak().with_data(|a_data| {
+            b.weak().with_data(|b_data| match (a_data, b_data) {
+                (OnionObject::String(s1), OnionObject::String(s2)) => {
+                    let old: Vec<&str> = s1.lines().collect();
+                    let new: Vec<&str> = s2.lines().collect();
+
+                    if (old.len() + 1)
+                        .checked_mul(new.len() + 1)
+                        .is_none_or(|cells| cells > MAX_DIFF_CELLS)
+                    {
+                        return Err(RuntimeError::InvalidOperation(
+                            format!(
+                                "diff inputs are too large ({} x {} lines)",
+                                old.len(),
+                                new.len()
+                            )
+                            .into(),
+                        ));
+                    }
+
+                    // lcs[i][j] 为 old[i..] 与 new[j..] 的最长公共子序列长度
+                    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+                    for i in (0..old.len()).rev() {
+                        for j in (0..new.len()).rev() {
+                            lcs[i][j] = if old[i] == new[j] {
+                                lcs[i + 1][j + 1] + 1
+                            } else {
+                                lcs[i + 1][j].max(lcs[i][j + 1])
+                            };
+                        }
+                    }
+
+                    let mut markers = vec![];
+                    let (mut i, mut j) = (0, 0);
+                    while i < old.len() || j < new.len() {
+                        let line = if i < old.len() && j < new.len() && old[i] == new[j] {
+                            i += 1;
+                            j += 1;
+                            format!(" {}", old[i - 1])
+                        } else if i < old.len()
+                            && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1])
+                        {
+                            i += 1;
+                            format!("-{}", old[i - 1])
+                        } else {
+                            j += 1;
+                            format!("+{}", new[j - 1])
+                        };
+                        markers.push(OnionObject::String(line.into()).stabilize());
+                    }
+                    Ok(OnionTuple::new_static_no_ref(&markers))
+                }
+                _ => Err(RuntimeError::InvalidOperation(
+                    "diff requires string arguments".to_string().into(),
+                )),
+            })
+        })
+    })
+}
+
+/// Concatenate a tuple of strings in one pass
+fn concat_all(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let parts = get_attr_direct(data, "parts".to_string())?;
+        parts.weak().with_data(|parts_data| match parts_data {
+            OnionObject::Tuple(tuple) => {
+                let elements = tuple.get_elements();
+                let mut total = 0;
+                for element in elements.iter() {
+                    match element {
+                        OnionObject::String(s) => total += s.len(),
+                        _ => {
+                            return Err(RuntimeError::InvalidOperation(
+                                "concat_all requires a tuple of strings".to_string().into(),
+                            ))
+                        }
+                    }
+                }
+                let mut result = String::with_capacity(total);
+                for element in elements.iter() {
+                    if let OnionObject::String(s) = element {
+                        result.push_str(s);
+                    }
+                }
+                Ok(OnionObject::String(result.into()).stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "concat_all requires a tuple of strings".to_string().into(),
+            )),
+        })
+    })
+}
+
+/// Count the lines of a string, splitting on '\n'
+fn count_lines(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let string = get_attr_direct(data, "string".to_string())?;
+        string.weak().with_data(|string_data| match string_data {
+            OnionObject::String(s) => {
+                Ok(OnionObject::Integer(s.split('\n').count() as i64).stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "count_lines requires a string argument".to_string().into(),
+            )),
+        })
+    })
+}
+
+/// Get the line at the given index, splitting on '\n'
+fn line_at(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let string = get_attr_direct(data, "string".to_string())?;
+        let index = get_attr_direct(data, "index".to_string())?;
+
+        string.weak().with_data(|string_data| {
+            index
+                .weak()
+                .with_data(|index_data| match (string_data, index_data) {
+                    (OnionObject::String(s), OnionObject::Integer(i)) => {
+                        let line = if *i < 0 {
+                            None
+                        } else {
+                            s.split('\n').nth(*i as usize)
+                        };
+                        match line {
+                            Some(line) => {
+                                Ok(OnionObject::String(line.to_string().into()).stabilize())
+                            }
+                            None => Err(RuntimeError::InvalidOperation(
+                                format!("line index {} out of range", i).into(),
+                            )),
+                        }
+                    }
+                    _ => Err(RuntimeError::InvalidOperation(
+                        "line_at requires string and integer arguments"
+                            .to_string()
+                            .into(),
+                    )),
+                })
+        })
+    })
+}
+
+/// Replace every non-ASCII character with a placeholder (empty by default)
+fn ascii_only(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let string = get_attr_direct(data, "string".to_string())?;
+        let placeholder = get_attr_direct(data, "placeholder".to_string())?;
+
+        string.weak().with_data(|string_data| {
+            placeholder
+                .weak()
+                .with_data(|placeholder_data| match (string_data, placeholder_data) {
+                    (OnionObject::String(s), OnionObject::String(p)) => {
+                        let mut result = String::with_capacity(s.len());
+                        for c in s.chars() {
+                            if c.is_ascii() {
+                                result.push(c);
+                            } else {
+                                result.push_str(p);
+                            }
+                        }
+                        Ok(OnionObject::String(result.into()).stabilize())
+                    }
+                    _ => Err(RuntimeError::InvalidOperation(
+                        "ascii_only requires string arguments".to_string().into(),
+                    )),
+                })
+        })
+    })
+}
+
+/// UTF-8 bytes of a string between two byte offsets on char boundaries
+fn to_bytes_slice(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let string = get_attr_direct(data, "string".to_string())?;
+        let start = get_attr_direct(data, "start".to_string())?;
+        let end = get_attr_direct(data, "end".to_string())?;
+
+        match (string.weak(), start.weak(), end.weak()) {
+            (OnionObject::String(s), OnionObject::Integer(start), OnionObject::Integer(end)) => {
+                if *start < 0 || *end < *start || *end as usize > s.len() {
+                    return Err(RuntimeError::InvalidOperation(
+                        format!(
+                            "byte range {}..{} out of bounds for length {}",
+                            start,
+                            end,
+                            s.len()
+                        )
+                        .into(),
+                    ));
+                }
+                let (start, end) = (*start as usize, *end as usize);
+                for offset in [start, end] {
+                    if !s.is_char_boundary(offset) {
+                        return Err(RuntimeError::InvalidOperation(
+                            format!("byte offset {} is not on a char boundary", offset).into(),
+                        ));
+                    }
+                }
+                Ok(OnionObject::Bytes(s.as_bytes()[start..end].to_vec().into()).stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "to_bytes_slice requires string and integer arguments"
+                    .to_string()
+                    .into(),
+            )),
+        }
+    })
+}
+
+/// Shorten a string to max_len characters, ending with ellipsis when truncated
+fn truncate(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let string = get_attr_direct(data, "string".to_string())?;
+        let max_len = get_attr_direct(data, "max_len".to_string())?;
+        let ellipsis = get_attr_direct(data, "ellipsis".to_string())?;
+
+        match (string.weak(), max_len.weak(), ellipsis.weak()) {
+            (
+                OnionObject::String(s),
+                OnionObject::Integer(max_len),
+                OnionObject::String(ellipsis),
+            ) => {
+                if *max_len < 0 {
+                    return Err(RuntimeError::InvalidOperation(
+                        "truncate max_len cannot be negative".to_string().into(),
+                    ));
+                }
+                let max_len = *max_len as usize;
+                if s.chars().count() <= max_len {
+                    return Ok(OnionObject::String(s.clone()).stabilize());
+                }
+                let ellipsis_len = ellipsis.chars().count();
+                let result: String = if ellipsis_len >= max_len {
+                    ellipsis.chars().take(max_len).collect()
+                } else {
+                    s.chars()
+                        .take(max_len - ellipsis_len)
+                        .chain(ellipsis.chars())
+                        .collect()
+                };
+                Ok(OnionObject::String(result.into()).stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "truncate requires string, integer and string arguments"
+                    .to_string()
+                    .into(),
+            )),
+        }
+    })
+}
+
+/// Left-pad a string with '0' to width characters, keeping a leading sign first
+fn zfill(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let string = get_attr_direct(data, "string".to_string())?;
+        let width = get_attr_direct(data, "width".to_string())?;
+
+        string.weak().with_data(|string_data| {
+            width
+                .weak()
+                .with_data(|width_data| match (string_data, width_data) {
+                    (OnionObject::String(s), OnionObject::Integer(width)) => {
+                        let len = s.chars().count();
+                        if *width <= len as i64 {
+                            return Ok(OnionObject::String(s.clone()).stabilize());
+                        }
+                        if *width as u64 > MAX_GENERATED_LEN as u64 {
+                            return Err(RuntimeError::InvalidOperation(
+                                format!("zfill width {} is too large", width).into(),
+                            ));
+                        }
+                        let fill = "0".repeat(*width as usize - len);
+                        let result = match s.strip_prefix(['-', '+']) {
+                            Some(digits) => format!("{}{}{}", &s[..1], fill, digits),
+                            None => format!("{}{}", fill, s),
+                        };
+                        Ok(OnionObject::String(result.into()).stabilize())
+                    }
+                    _ => Err(RuntimeError::InvalidOperation(
+                        "zfill requires string and integer arguments"
+                            .to_string()
+                            .into(),
+                    )),
+                })
+        })
+    })
+}
+
+/// Count occurrences of each distinct character, in order of first appearance
+fn count_chars(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let string = get_attr_direct(data, "string".to_string())?;
+        string.weak().with_data(|string_data| match string_data {
+            OnionObject::String(s) => {
+                let mut counts: IndexMap<String, i64> = IndexMap::new();
+                for c in s.chars() {
+                    *counts.entry(c.to_string()).or_insert(0) += 1;
+                }
+                Ok(build_named_dict(
+                    counts
+                        .into_iter()
+                        .map(|(c, n)| (c, OnionObject::Integer(n).stabilize()))
+                        .collect(),
+                ))
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "count_chars requires a string argument".to_string().into(),
+            )),
+        })
+    })
+}
+
+/// Split a string at a character index into a (before, after) tuple
+fn split_at(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    use onion_vm::types::tuple::OnionTuple;
+
+    argument.weak().with_data(|data| {
+        let string = get_attr_direct(data, "string".to_string())?;
+        let index = get_attr_direct(data, "index".to_string())?;
+
+        string.weak().with_data(|string_data| {
+            index
+                .weak()
+                .with_data(|index_data| match (string_data, index_data) {
+                    (OnionObject::String(s), OnionObject::Integer(i)) => {
+                        let chars = (*i).max(0) as usize;
+                        let byte_index = s
+                            .char_indices()
+                            .nth(chars)
+                            .map(|(offset, _)| offset)
+                            .unwrap_or(s.len());
+                        let (before, after) = s.split_at(byte_index);
+                        Ok(OnionTuple::new_static_no_ref(&[
+                            OnionObject::String(before.to_string().into()).stabilize(),
+                            OnionObject::String(after.to_string().into()).stabilize(),
+                        ]))
+                    }
+                    _ => Err(RuntimeError::InvalidOperation(
+                        "split_at requires string and integer arguments"
+                            .to_string()
+                            .into(),
+                    )),
+                })
+        })
+    })
+}
+
+/// Collect the elements of a tuple of strings
+fn string_elements(obj: &OnionObject, func_name: &str) -> Result<Vec<String>, RuntimeError> {
+    match obj {
+        OnionObject::Tuple(tuple) => tuple
+            .get_elements()
+            .iter()
+            .map(|element| match element {
+                OnionObject::String(s) => Ok(s.as_ref().clone()),
+                _ => Err(RuntimeError::InvalidOperation(
+                    format!("{} requires a tuple of strings", func_name).into(),
+                )),
+            })
+            .collect(),
+        _ => Err(RuntimeError::InvalidOperation(
+            format!("{} requires a tuple of strings", func_name).into(),
+        )),
+    }
+}
+
+/// Longest run of characters shared by all sequences, compared front to back
+fn common_chars<I>(mut sequences: impl Iterator<Item = I>) -> Vec<char>
+where
+    I: Iterator<Item = char>,
+{
+    let mut common: Vec<char> = match sequences.next() {
+        Some(first) => first.collect(),
+        None => return vec![],
+    };
+    for sequence in sequences {
+        let shared = common
+            .iter()
+            .zip(sequence)
+            .take_while(|(a, b)| **a == *b)
+            .count();
+        common.truncate(shared);
+    }
+    common
+}
+
+/// Longest prefix shared by every string in a tuple
+fn common_prefix(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let strings = get_attr_direct(data, "strings".to_string())?;
+        let strings = strings
+            .weak()
+            .with_data(|strings_data| string_elements(strings_data, "common_prefix"))?;
+        let prefix: String = common_chars(strings.iter().map(|s| s.chars()))
+            .into_iter()
+            .collect();
+        Ok(OnionObject::String(prefix.into()).stabilize())
+    })
+}
+
+/// Longest suffix shared by every string in a tuple
+fn common_suffix(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let strings = get_attr_direct(data, "strings".to_string())?;
+        let strings = strings
+            .weak()
+            .with_data(|strings_data| string_elements(strings_data, "common_suffix"))?;
+        let suffix: String = common_chars(strings.iter().map(|s| s.chars().rev()))
+            .into_iter()
+            .rev()
+            .collect();
+        Ok(OnionObject::String(suffix.into()).stabilize())
+    })
+}
+
+/// Split a string on any of the characters in delimiters, dropping empty tokens
+fn split_any(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    use onion_vm::types::tuple::OnionTuple;
+
+    argument.weak().with_data(|data| {
+        let string = get_attr_direct(data, "string".to_string())?;
+        let delimiters = get_attr_direct(data, "delimiters".to_string())?;
+
+        string.weak().with_data(|string_data| {
+            delimiters
+                .weak()
+                .with_data(|delimiters_data| match (string_data, delimiters_data) {
+                    (OnionObject::String(s), OnionObject::String(delims)) => {
+                        let tokens: Vec<_> = s
+                            .split(|c: char| delims.contains(c))
+                            .filter(|token| !token.is_empty())
+                            .map(|token| OnionObject::String(token.to_string().into()).stabilize())
+                            .collect();
+                        Ok(OnionTuple::new_static_no_ref(&tokens))
+                    }
+                    _ => Err(RuntimeError::InvalidOperation(
+                        "split_any requires string arguments".to_string().into(),
+                    )),
+                })
+        })
+    })
+}
+
+/// Count whitespace-separated words
+fn word_count(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let string = get_attr_direct(data, "string".to_string())?;
+        string.weak().with_data(|string_data| match string_data {
+            OnionObject::String(s) => {
+                Ok(OnionObject::Integer(s.split_whitespace().count() as i64).stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "word_count requires a string argument".to_string().into(),
+            )),
+        })
+    })
+}
+
+/// Split a string into whitespace-separated words
+fn words(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    use onion_vm::types::tuple::OnionTuple;
+
+    argument.weak().with_data(|data| {
+        let string = get_attr_direct(data, "string".to_string())?;
+        string.weak().with_data(|string_data| match string_data {
+            OnionObject::String(s) => {
+                let words: Vec<_> = s
+                    .split_whitespace()
+                    .map(|word| OnionObject::String(word.to_string().into()).stabilize())
+                    .collect();
+                Ok(OnionTuple::new_static_no_ref(&words))
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "words requires a string argument".to_string().into(),
+            )),
+        })
+    })
+}
+
+/// Replace tabs with spaces up to the next tab stop, tracking the column per line
+fn expand_tabs(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let string = get_attr_direct(data, "string".to_string())?;
+        let tab_size = get_attr_direct(data, "tab_size".to_string())?;
+
+        string.weak().with_data(|string_data| {
+            tab_size
+                .weak()
+                .with_data(|tab_size_data| match (string_data, tab_size_data) {
+                    (OnionObject::String(s), OnionObject::Integer(tab_size)) => {
+                        if *tab_size < 0 {
+                            return Err(RuntimeError::InvalidOperation(
+                                "expand_tabs tab_size cannot be negative".to_string().into(),
+                            ));
+                        }
+                        let tab_size = *tab_size as usize;
+                        let mut result = String::with_capacity(s.len());
+                        let mut column = 0;
+                        for c in s.chars() {
+                            match c {
+                                '\t' => {
+                                    if tab_size > 0 {
+                                        let spaces = tab_size - column % tab_size;
+                                        if result.len().saturating_add(spaces) > MAX_GENERATED_LEN {
+                                            return Err(RuntimeError::InvalidOperation(
+                                                "expand_tabs result would be too large"
+                                                    .to_string()
+                                                    .into(),
+                                            ));
+                                        }
+                                        result.push_str(&" ".repeat(spaces));
+                                        column += spaces;
+                                    }
+                                }
+                                '\n' | '\r' => {
+                                    result.push(c);
+                                    column = 0;
+                                }
+                                _ => {
+                                    result.push(c);
+                                    column += 1;
+                                }
+                            }
+                        }
+                        Ok(OnionObject::String(result.into()).stabilize())
+                    }
+                    _ => Err(RuntimeError::InvalidOperation(
+                        "expand_tabs requires string and integer arguments"
+                            .to_string()
+                            .into(),
+                    )),
+                })
+        })
+    })
+}
+
+/// Keep the first and last characters visible and mask everything between
+fn mask(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let string = get_attr_direct(data, "string".to_string())?;
+        let visible_start = get_attr_direct(data, "visible_start".to_string())?;
+        let visible_end = get_attr_direct(data, "visible_end".to_string())?;
+        let mask_char = get_attr_direct(data, "mask_char".to_string())?;
+
+        match (
+            string.weak(),
+            visible_start.weak(),
+            visible_end.weak(),
+            mask_char.weak(),
+        ) {
+            (
+                OnionObject::String(s),
+                OnionObject::Integer(start),
+                OnionObject::Integer(end),
+                OnionObject::String(mask_char),
+            ) => {
+                if *start < 0 || *end < 0 {
+                    return Err(RuntimeError::InvalidOperation(
+                        "mask visible counts cannot be negative".to_string().into(),
+                    ));
+                }
+                let mut mask_chars = mask_char.chars();
+                let mask_char = match (mask_chars.next(), mask_chars.next()) {
+                    (Some(c), None) => c,
+                    _ => {
+                        return Err(RuntimeError::InvalidOperation(
+                            "mask_char must be a single character".to_string().into(),
+                        ))
+                    }
+                };
+                let chars: Vec<char> = s.chars().collect();
+                let (start, end) = (*start as usize, *end as usize);
+                if start.saturating_add(end) >= chars.len() {
+                    return Ok(OnionObject::String(s.clone()).stabilize());
+                }
+                let masked: String = chars[..start]
+                    .iter()
+                    .copied()
+                    .chain(std::iter::repeat(mask_char).take(chars.len() - start - end))
+                    .chain(chars[chars.len() - end..].iter().copied())
+                    .collect();
+                Ok(OnionObject::String(masked.into()).stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "mask requires string, integer, integer and string arguments"
+                    .to_string()
+                    .into(),
+            )),
+        }
+    })
+}
+
+/// Terminal display width, counting wide East Asian characters as two columns
+fn display_width(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    use unicode_width::UnicodeWidthStr;
+
+    argument.weak().with_data(|data| {
+        let string = get_attr_direct(data, "string".to_string())?;
+        string.weak().with_data(|string_data| match string_data {
+            OnionObject::String(s) => Ok(OnionObject::Integer(s.width() as i64).stabilize()),
+            _ => Err(RuntimeError::InvalidOperation(
+                "display_width requires a string argument"
+                    .to_string()
+                    .into(),
+            )),
+        })
+    })
+}
+
+/// Characters escaped by encode_uri_component: everything except A-Z a-z 0-9 - _ . ! ~ * ' ( )
+const URI_COMPONENT: &percent_encoding::AsciiSet = &percent_encoding::NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'!')
+    .remove(b'~')
+    .remove(b'*')
+    .remove(b'\'')
+    .remove(b'(')
+    .remove(b')');
+
+/// Percent-encode a string for use as a URI component
+fn encode_uri_component(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let string = get_attr_direct(data, "string".to_string())?;
+        string.weak().with_data(|string_data| match string_data {
+            OnionObject::String(s) => {
+                let encoded = percent_encoding::utf8_percent_encode(s, URI_COMPONENT).to_string();
+                Ok(OnionObject::String(encoded.into()).stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "encode_uri_component requires a string argument"
+                    .to_string()
+                    .into(),
+            )),
+        })
+    })
+}
+
+/// Decode a percent-encoded URI component
+fn decode_uri_component(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let string = get_attr_direct(data, "string".to_string())?;
+        string.weak().with_data(|string_data| match string_data {
+            OnionObject::String(s) => {
+                let bytes = s.as_bytes();
+                for (i, _) in bytes.iter().enumerate().filter(|(_, b)| **b == b'%') {
+                    let valid = bytes.len() > i + 2
+                        && bytes[i + 1].is_ascii_hexdigit()
+                        && bytes[i + 2].is_ascii_hexdigit();
+                    if !valid {
+                        return Err(RuntimeError::InvalidOperation(
+                            format!("Malformed percent escape at byte {}", i).into(),
+                        ));
+                    }
+                }
+                match percent_encoding::percent_decode_str(s).decode_utf8() {
+                    Ok(decoded) => Ok(OnionObject::String(decoded.into_owned().into()).stabilize()),
+                    Err(_) => Err(RuntimeError::InvalidOperation(
+                        "decoded URI component is not valid UTF-8"
+                            .to_string()
+                            .into(),
+                    )),
+                }
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "decode_uri_component requires a string argument"
+                    .to_string()
+                    .into(),
+            )),
+        })
+    })
+}
+
+/// Split a string into lines, keeping each line's '\n' or "\r\n" terminator
+fn splitlines_keepends(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    use onion_vm::types::tuple::OnionTuple;
+
+    argument.weak().with_data(|data| {
+        let string = get_attr_direct(data, "string".to_string())?;
+        string.weak().with_data(|string_data| match string_data {
+            OnionObject::String(s) => {
+                let lines: Vec<_> = s
+                    .split_inclusive('\n')
+                    .map(|line| OnionObject::String(line.to_string().into()).stabilize())
+                    .collect();
+                Ok(OnionTuple::new_static_no_ref(&lines))
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "splitlines_keepends requires a string argument"
+                    .to_string()
+                    .into(),
+            )),
+        })
+    })
+}
+
+/// Longest contiguous substring shared by two strings
+fn longest_common_substring(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let a = get_attr_direct(data, "a".to_string())?;
+        let b = get_attr_direct(data, "b".to_string())?;
+
+        match (a.weak(), b.weak()) {
+            (OnionObject::String(a), OnionObject::String(b)) => {
+                let a: Vec<char> = a.chars().collect();
+                let b: Vec<char> = b.chars().collect();
+                // lengths[j + 1] is the common suffix length of a[..=i] and b[..=j]
+                let mut lengths = vec![0usize; b.len() + 1];
+                let (mut best_len, mut best_end) = (0, 0);
+                for (i, ca) in a.iter().enumerate() {
+                    for j in (0..b.len()).rev() {
+                        if *ca == b[j] {
+                            lengths[j + 1] = lengths[j] + 1;
+                            if lengths[j + 1] > best_len {
+                                best_len = lengths[j + 1];
+                                best_end = i + 1;
+                            }
+                        } else {
+                            lengths[j + 1] = 0;
+                        }
+                    }
+                }
+                let substring: String = a[best_end - best_len..best_end].iter().collect();
+                Ok(OnionObject::String(substring.into()).stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "longest_common_substring requires string arguments"
+                    .to_string()
+                    .into(),
+            )),
+        }
+    })
+}
+
+/// Trim any of the given characters from both ends of a string
+fn trim_chars(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let string = get_attr_direct(data, "string".to_string())?;
+        let chars = get_attr_direct(data, "chars".to_string())?;
+
+        match (string.weak(), chars.weak()) {
+            (OnionObject::String(s), OnionObject::String(chars)) => {
+                let trimmed = s.trim_matches(|c: char| chars.contains(c));
+                Ok(OnionObject::String(trimmed.to_string().into()).stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "trim_chars requires string arguments".to_string().into(),
+            )),
+        }
+    })
+}
+
+/// Pad a string to width characters, aligned left, right or center
+fn justify(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let string = get_attr_direct(data, "string".to_string())?;
+        let width = get_attr_direct(data, "width".to_string())?;
+        let mode = get_attr_direct(data, "mode".to_string())?;
+        let pad_char = get_attr_direct(data, "pad_char".to_string())?;
+
+        match (string.weak(), width.weak(), mode.weak(), pad_char.weak()) {
+            (
+                OnionObject::String(s),
+                OnionObject::Integer(width),
+                OnionObject::String(mode),
+                OnionObject::String(pad),
+            ) => {
+                if *width > MAX_GENERATED_LEN as i64 {
+                    return Err(RuntimeError::InvalidOperation(
+                        format!("justify width {} is too large", width).into(),
+                    ));
+                }
+                let pad_count = ((*width).max(0) as usize).saturating_sub(s.chars().count());
+                let (left, right) = match mode.as_str() {
+                    "left" => (0, pad_count),
+                    "right" => (pad_count, 0),
+                    "center" => (pad_count / 2, pad_count - pad_count / 2),
+                    _ => {
+                        return Err(RuntimeError::InvalidOperation(
+                            format!("justify mode must be left, right or center, got {}", mode)
+                                .into(),
+                        ))
+                    }
+                };
+                let pad = pad.chars().next().unwrap_or(' ').to_string();
+                let justified = format!("{}{}{}", pad.repeat(left), s, pad.repeat(right));
+                Ok(OnionObject::String(justified.into()).stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "justify requires string, integer, string and string arguments"
+                    .to_string()
+                    .into(),
+            )),
+        }
+    })
+}
+
 pub fn build_module() -> OnionStaticObject {
     let mut module = IndexMap::new();
 
-    // length 函数
-    let mut length_params = IndexMap::new();
-    length_params.insert(
-        "string".to_string(),
-        OnionObject::Undefined(Some("String to get length".to_string().into())).stabilize(),
+    // length 函数
+    let mut length_params = IndexMap::new();
+    length_params.insert(
+        "string".to_string(),
+        OnionObject::Undefined(Some("String to get length".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "length".to_string(),
+        wrap_native_function(
+            &build_named_dict(length_params),
+            None,
+            None,
+            "string::length".to_string(),
+            &length,
+        ),
+    );
+
+    // trim 函数
+    let mut trim_params = IndexMap::new();
+    trim_params.insert(
+        "string".to_string(),
+        OnionObject::Undefined(Some("String to trim".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "trim".to_string(),
+        wrap_native_function(
+            &build_named_dict(trim_params),
+            None,
+            None,
+            "string::trim".to_string(),
+            &trim,
+        ),
+    );
+
+    // uppercase 函数
+    let mut uppercase_params = IndexMap::new();
+    uppercase_params.insert(
+        "string".to_string(),
+        OnionObject::Undefined(Some("String to convert to uppercase".to_string().into()))
+            .stabilize(),
+    );
+    module.insert(
+        "uppercase".to_string(),
+        wrap_native_function(
+            &build_named_dict(uppercase_params),
+            None,
+            None,
+            "string::uppercase".to_string(),
+            &uppercase,
+        ),
+    );
+
+    // lowercase 函数
+    let mut lowercase_params = IndexMap::new();
+    lowercase_params.insert(
+        "string".to_string(),
+        OnionObject::Undefined(Some("String to convert to lowercase".to_string().into()))
+            .stabilize(),
+    );
+    module.insert(
+        "lowercase".to_string(),
+        wrap_native_function(
+            &build_named_dict(lowercase_params),
+            None,
+            None,
+            "string::lowercase".to_string(),
+            &lowercase,
+        ),
+    );
+
+    // contains 函数
+    let mut contains_params = IndexMap::new();
+    contains_params.insert(
+        "string".to_string(),
+        OnionObject::Undefined(Some("String to search within".to_string().into())).stabilize(),
+    );
+    contains_params.insert(
+        "substring".to_string(),
+        OnionObject::Undefined(Some("Substring to search for".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "contains".to_string(),
+        wrap_native_function(
+            &build_named_dict(contains_params),
+            None,
+            None,
+            "string::contains".to_string(),
+            &contains,
+        ),
+    );
+
+    // concat 函数
+    let mut concat_params = IndexMap::new();
+    concat_params.insert(
+        "a".to_string(),
+        OnionObject::Undefined(Some("First string to concatenate".to_string().into())).stabilize(),
+    );
+    concat_params.insert(
+        "b".to_string(),
+        OnionObject::Undefined(Some("Second string to concatenate".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "concat".to_string(),
+        wrap_native_function(
+            &build_named_dict(concat_params),
+            None,
+            None,
+            "string::concat".to_string(),
+            &concat,
+        ),
+    );
+
+    // split 函数
+    let mut split_params = IndexMap::new();
+    split_params.insert(
+        "string".to_string(),
+        OnionObject::Undefined(Some("String to split".to_string().into())).stabilize(),
+    );
+    split_params.insert(
+        "delimiter".to_string(),
+        OnionObject::Undefined(Some("Delimiter to split by".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "split".to_string(),
+        wrap_native_function(
+            &build_named_dict(split_params),
+            None,
+            None,
+            "string::split".to_string(),
+            &split,
+        ),
+    );
+
+    // replace 函数
+    let mut replace_params = IndexMap::new();
+    replace_params.insert(
+        "string".to_string(),
+        OnionObject::Undefined(Some("String to perform replacement on".to_string().into()))
+            .stabilize(),
+    );
+    replace_params.insert(
+        "from".to_string(),
+        OnionObject::Undefined(Some("Substring to replace".to_string().into())).stabilize(),
+    );
+    replace_params.insert(
+        "to".to_string(),
+        OnionObject::Undefined(Some("Replacement string".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "replace".to_string(),
+        wrap_native_function(
+            &build_named_dict(replace_params),
+            None,
+            None,
+            "string::replace".to_string(),
+            &replace,
+        ),
+    );
+
+    // substr 函数
+    let mut substr_params = IndexMap::new();
+    substr_params.insert(
+        "string".to_string(),
+        OnionObject::Undefined(Some("String to extract substring from".to_string().into()))
+            .stabilize(),
+    );
+    substr_params.insert(
+        "start".to_string(),
+        OnionObject::Undefined(Some("Start index".to_string().into())).stabilize(),
+    );
+    substr_params.insert(
+        "length".to_string(),
+        OnionObject::Undefined(Some("Length of substring".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "substr".to_string(),
+        wrap_native_function(
+            &build_named_dict(substr_params),
+            None,
+            None,
+            "string::substr".to_string(),
+            &substr,
+        ),
+    );
+
+    // index_of 函数
+    let mut index_of_params = IndexMap::new();
+    index_of_params.insert(
+        "string".to_string(),
+        OnionObject::Undefined(Some("String to search in".to_string().into())).stabilize(),
+    );
+    index_of_params.insert(
+        "substring".to_string(),
+        OnionObject::Undefined(Some("Substring to find".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "index_of".to_string(),
+        wrap_native_function(
+            &build_named_dict(index_of_params),
+            None,
+            None,
+            "string::index_of".to_string(),
+            &index_of,
+        ),
+    );
+
+    // starts_with 函数
+    let mut starts_with_params = IndexMap::new();
+    starts_with_params.insert(
+        "string".to_string(),
+        OnionObject::Undefined(Some("String to check".to_string().into())).stabilize(),
+    );
+    starts_with_params.insert(
+        "prefix".to_string(),
+        OnionObject::Undefined(Some("Prefix to check for".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "starts_with".to_string(),
+        wrap_native_function(
+            &build_named_dict(starts_with_params),
+            None,
+            None,
+            "string::starts_with".to_string(),
+            &starts_with,
+        ),
+    );
+
+    // ends_with 函数
+    let mut ends_with_params = IndexMap::new();
+    ends_with_params.insert(
+        "string".to_string(),
+        OnionObject::Undefined(Some("String to check".to_string().into())).stabilize(),
+    );
+    ends_with_params.insert(
+        "suffix".to_string(),
+        OnionObject::Undefined(Some("Suffix to check for".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "ends_with".to_string(),
+        wrap_native_function(
+            &build_named_dict(ends_with_params),
+            None,
+            None,
+            "string::ends_with".to_string(),
+            &ends_with,
+        ),
+    );
+
+    // repeat 函数
+    let mut repeat_params = IndexMap::new();
+    repeat_params.insert(
+        "string".to_string(),
+        OnionObject::Undefined(Some("String to repeat".to_string().into())).stabilize(),
+    );
+    repeat_params.insert(
+        "count".to_string(),
+        OnionObject::Undefined(Some("Number of times to repeat".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "repeat".to_string(),
+        wrap_native_function(
+            &build_named_dict(repeat_params),
+            None,
+            None,
+            "string::repeat".to_string(),
+            &repeat,
+        ),
+    );
+
+    // pad_left 函数
+    let mut pad_left_params = IndexMap::new();
+    pad_left_params.insert(
+        "string".to_string(),
+        OnionObject::Undefined(Some("String to pad".to_string().into())).stabilize(),
+    );
+    pad_left_params.insert(
+        "length".to_string(),
+        OnionObject::Undefined(Some("Target length".to_string().into())).stabilize(),
+    );
+    pad_left_params.insert(
+        "pad_char".to_string(),
+        OnionObject::Undefined(Some("Character to pad with".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "pad_left".to_string(),
+        wrap_native_function(
+            &build_named_dict(pad_left_params),
+            None,
+            None,
+            "string::pad_left".to_string(),
+            &pad_left,
+        ),
+    );
+
+    // pad_right 函数
+    let mut pad_right_params = IndexMap::new();
+    pad_right_params.insert(
+        "string".to_string(),
+        OnionObject::Undefined(Some("String to pad".to_string().into())).stabilize(),
+    );
+    pad_right_params.insert(
+        "length".to_string(),
+        OnionObject::Undefined(Some("Target length".to_string().into())).stabilize(),
+    );
+    pad_right_params.insert(
+        "pad_char".to_string(),
+        OnionObject::Undefined(Some("Character to pad with".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "pad_right".to_string(),
+        wrap_native_function(
+            &build_named_dict(pad_right_params),
+            None,
+            None,
+            "string::pad_right".to_string(),
+            &pad_right,
+        ),
+    );
+
+    // is_empty 函数
+    let mut is_empty_params = IndexMap::new();
+    is_empty_params.insert(
+        "string".to_string(),
+        OnionObject::Undefined(Some("String to check if empty".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "is_empty".to_string(),
+        wrap_native_function(
+            &build_named_dict(is_empty_params),
+            None,
+            None,
+            "string::is_empty".to_string(),
+            &is_empty,
+        ),
+    );
+
+    // reverse 函数
+    let mut reverse_params = IndexMap::new();
+    reverse_params.insert(
+        "string".to_string(),
+        OnionObject::Undefined(Some("String to reverse".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "reverse".to_string(),
+        wrap_native_function(
+            &build_named_dict(reverse_params),
+            None,
+            None,
+            "string::reverse".to_string(),
+            &reverse,
+        ),
+    );
+
+    // similarity_ratio 函数
+    let mut similarity_ratio_params = IndexMap::new();
+    similarity_ratio_params.insert(
+        "a".to_string(),
+        OnionObject::Undefined(Some("First string to compare".to_string().into())).stabilize(),
+    );
+    similarity_ratio_params.insert(
+        "b".to_string(),
+        OnionObject::Undefined(Some("Second string to compare".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "similarity_ratio".to_string(),
+        wrap_native_function(
+            &build_named_dict(similarity_ratio_params),
+            None,
+            None,
+            "string::similarity_ratio".to_string(),
+            &similarity_ratio,
+        ),
+    );
+
+    // hex_escape 函数
+    let mut hex_escape_params = IndexMap::new();
+    hex_escape_params.insert(
+        "string".to_string(),
+        OnionObject::Undefined(Some("String to escape".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "hex_escape".to_string(),
+        wrap_native_function(
+            &build_named_dict(hex_escape_params),
+            None,
+            None,
+            "string::hex_escape".to_string(),
+            &hex_escape,
+        ),
+    );
+
+    // hex_unescape 函数
+    let mut hex_unescape_params = IndexMap::new();
+    hex_unescape_params.insert(
+        "string".to_string(),
+        OnionObject::Undefined(Some("String containing \\xNN escapes".to_string().into()))
+            .stabilize(),
+    );
+    module.insert(
+        "hex_unescape".to_string(),
+        wrap_native_function(
+            &build_named_dict(hex_unescape_params),
+            None,
+            None,
+            "string::hex_unescape".to_string(),
+            &hex_unescape,
+        ),
+    );
+
+    // diff 函数
+    let mut diff_params = IndexMap::new();
+    diff_params.insert(
+        "a".to_string(),
+        OnionObject::Undefined(Some("Original text".to_string().into())).stabilize(),
+    );
+    diff_params.insert(
+        "b".to_string(),
+        OnionObject::Undefined(Some("Updated text".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "diff".to_string(),
+        wrap_native_function(
+            &build_named_dict(diff_params),
+            None,
+            None,
+            "string::diff".to_string(),
+            &diff,
+        ),
+    );
+
+    // concat_all 函数
+    let mut concat_all_params = IndexMap::new();
+    concat_all_params.insert(
+        "parts".to_string(),
+        OnionObject::Undefined(Some("Tuple of strings to concatenate".to_string().into()))
+            .stabilize(),
     );
     module.insert(
-        "length".to_string(),
+        "concat_all".to_string(),
         wrap_native_function(
-            &build_named_dict(length_params),
+            &build_named_dict(concat_all_params),
             None,
             None,
-            "string::length".to_string(),
-            &length,
+            "string::concat_all".to_string(),
+            &concat_all,
         ),
     );
 
-    // trim 函数
-    let mut trim_params = IndexMap::new();
-    trim_params.insert(
+    // count_lines 函数
+    let mut count_lines_params = IndexMap::new();
+    count_lines_params.insert(
         "string".to_string(),
-        OnionObject::Undefined(Some("String to trim".to_string().into())).stabilize(),
+        OnionObject::Undefined(Some("String to count lines in".to_string().into())).stabilize(),
     );
     module.insert(
-        "trim".to_string(),
+        "count_lines".to_string(),
         wrap_native_function(
-            &build_named_dict(trim_params),
+            &build_named_dict(count_lines_params),
             None,
             None,
-            "string::trim".to_string(),
-            &trim,
+            "string::count_lines".to_string(),
+            &count_lines,
         ),
     );
 
-    // uppercase 函数
-    let mut uppercase_params = IndexMap::new();
-    uppercase_params.insert(
+    // line_at 函数
+    let mut line_at_params = IndexMap::new();
+    line_at_params.insert(
         "string".to_string(),
-        OnionObject::Undefined(Some("String to convert to uppercase".to_string().into()))
-            .stabilize(),
+        OnionObject::Undefined(Some("String to take the line from".to_string().into())).stabilize(),
+    );
+    line_at_params.insert(
+        "index".to_string(),
+        OnionObject::Undefined(Some("Zero-based line index".to_string().into())).stabilize(),
     );
     module.insert(
-        "uppercase".to_string(),
+        "line_at".to_string(),
         wrap_native_function(
-            &build_named_dict(uppercase_params),
+            &build_named_dict(line_at_params),
             None,
             None,
-            "string::uppercase".to_string(),
-            &uppercase,
+            "string::line_at".to_string(),
+            &line_at,
         ),
     );
 
-    // lowercase 函数
-    let mut lowercase_params = IndexMap::new();
-    lowercase_params.insert(
+    // ascii_only 函数
+    let mut ascii_only_params = IndexMap::new();
+    ascii_only_params.insert(
         "string".to_string(),
-        OnionObject::Undefined(Some("String to convert to lowercase".to_string().into()))
-            .stabilize(),
+        OnionObject::Undefined(Some("String to sanitize".to_string().into())).stabilize(),
+    );
+    ascii_only_params.insert(
+        "placeholder".to_string(),
+        OnionObject::String("".to_string().into()).stabilize(),
     );
     module.insert(
-        "lowercase".to_string(),
+        "ascii_only".to_string(),
         wrap_native_function(
-            &build_named_dict(lowercase_params),
+            &build_named_dict(ascii_only_params),
             None,
             None,
-            "string::lowercase".to_string(),
-            &lowercase,
+            "string::ascii_only".to_string(),
+            &ascii_only,
         ),
     );
 
-    // contains 函数
-    let mut contains_params = IndexMap::new();
-    contains_params.insert(
+    // to_bytes_slice 函数
+    let mut to_bytes_slice_params = IndexMap::new();
+    to_bytes_slice_params.insert(
         "string".to_string(),
-        OnionObject::Undefined(Some("String to search within".to_string().into())).stabilize(),
+        OnionObject::Undefined(Some("Source string".to_string().into())).stabilize(),
     );
-    contains_params.insert(
-        "substring".to_string(),
-        OnionObject::Undefined(Some("Substring to search for".to_string().into())).stabilize(),
+    to_bytes_slice_params.insert(
+        "start".to_string(),
+        OnionObject::Undefined(Some("Start byte offset (inclusive)".to_string().into()))
+            .stabilize(),
+    );
+    to_bytes_slice_params.insert(
+        "end".to_string(),
+        OnionObject::Undefined(Some("End byte offset (exclusive)".to_string().into())).stabilize(),
     );
     module.insert(
-        "contains".to_string(),
+        "to_bytes_slice".to_string(),
         wrap_native_function(
-            &build_named_dict(contains_params),
+            &build_named_dict(to_bytes_slice_params),
             None,
             None,
-            "string::contains".to_string(),
-            &contains,
+            "string::to_bytes_slice".to_string(),
+            &to_bytes_slice,
         ),
     );
 
-    // concat 函数
-    let mut concat_params = IndexMap::new();
-    concat_params.insert(
-        "a".to_string(),
-        OnionObject::Undefined(Some("First string to concatenate".to_string().into())).stabilize(),
+    // truncate 函数
+    let mut truncate_params = IndexMap::new();
+    truncate_params.insert(
+        "string".to_string(),
+        OnionObject::Undefined(Some("String to shorten".to_string().into())).stabilize(),
     );
-    concat_params.insert(
-        "b".to_string(),
-        OnionObject::Undefined(Some("Second string to concatenate".to_string().into())).stabilize(),
+    truncate_params.insert(
+        "max_len".to_string(),
+        OnionObject::Undefined(Some(
+            "Maximum length in characters, including the ellipsis"
+                .to_string()
+                .into(),
+        ))
+        .stabilize(),
+    );
+    truncate_params.insert(
+        "ellipsis".to_string(),
+        OnionObject::String("...".to_string().into()).stabilize(),
     );
     module.insert(
-        "concat".to_string(),
+        "truncate".to_string(),
         wrap_native_function(
-            &build_named_dict(concat_params),
+            &build_named_dict(truncate_params),
             None,
             None,
-            "string::concat".to_string(),
-            &concat,
+            "string::truncate".to_string(),
+            &truncate,
         ),
     );
 
-    // split 函数
-    let mut split_params = IndexMap::new();
-    split_params.insert(
+    // zfill 函数
+    let mut zfill_params = IndexMap::new();
+    zfill_params.insert(
         "string".to_string(),
-        OnionObject::Undefined(Some("String to split".to_string().into())).stabilize(),
+        OnionObject::Undefined(Some("String to pad".to_string().into())).stabilize(),
     );
-    split_params.insert(
-        "delimiter".to_string(),
-        OnionObject::Undefined(Some("Delimiter to split by".to_string().into())).stabilize(),
+    zfill_params.insert(
+        "width".to_string(),
+        OnionObject::Undefined(Some("Minimum width in characters".to_string().into())).stabilize(),
     );
     module.insert(
-        "split".to_string(),
+        "zfill".to_string(),
         wrap_native_function(
-            &build_named_dict(split_params),
+            &build_named_dict(zfill_params),
             None,
             None,
-            "string::split".to_string(),
-            &split,
+            "string::zfill".to_string(),
+            &zfill,
         ),
     );
 
-    // replace 函数
-    let mut replace_params = IndexMap::new();
-    replace_params.insert(
+    // count_chars 函数
+    let mut count_chars_params = IndexMap::new();
+    count_chars_params.insert(
         "string".to_string(),
-        OnionObject::Undefined(Some("String to perform replacement on".to_string().into()))
+        OnionObject::Undefined(Some("String to count characters in".to_string().into()))
             .stabilize(),
     );
-    replace_params.insert(
-        "from".to_string(),
-        OnionObject::Undefined(Some("Substring to replace".to_string().into())).stabilize(),
+    module.insert(
+        "count_chars".to_string(),
+        wrap_native_function(
+            &build_named_dict(count_chars_params),
+            None,
+            None,
+            "string::count_chars".to_string(),
+            &count_chars,
+        ),
     );
-    replace_params.insert(
-        "to".to_string(),
-        OnionObject::Undefined(Some("Replacement string".to_string().into())).stabilize(),
+
+    // split_at 函数
+    let mut split_at_params = IndexMap::new();
+    split_at_params.insert(
+        "string".to_string(),
+        OnionObject::Undefined(Some("String to split".to_string().into())).stabilize(),
+    );
+    split_at_params.insert(
+        "index".to_string(),
+        OnionObject::Undefined(Some(
+            "Character index to split at, clamped to [0, len]"
+                .to_string()
+                .into(),
+        ))
+        .stabilize(),
     );
     module.insert(
-        "replace".to_string(),
+        "split_at".to_string(),
         wrap_native_function(
-            &build_named_dict(replace_params),
+            &build_named_dict(split_at_params),
             None,
             None,
-            "string::replace".to_string(),
-            &replace,
+            "string::split_at".to_string(),
+            &split_at,
         ),
     );
 
-    // substr 函数
-    let mut substr_params = IndexMap::new();
-    substr_params.insert(
-        "string".to_string(),
-        OnionObject::Undefined(Some("String to extract substring from".to_string().into()))
-            .stabilize(),
+    // common_prefix 函数
+    let mut common_prefix_params = IndexMap::new();
+    common_prefix_params.insert(
+        "strings".to_string(),
+        OnionObject::Undefined(Some("Tuple of strings".to_string().into())).stabilize(),
     );
-    substr_params.insert(
-        "start".to_string(),
-        OnionObject::Undefined(Some("Start index".to_string().into())).stabilize(),
+    module.insert(
+        "common_prefix".to_string(),
+        wrap_native_function(
+            &build_named_dict(common_prefix_params),
+            None,
+            None,
+            "string::common_prefix".to_string(),
+            &common_prefix,
+        ),
     );
-    substr_params.insert(
-        "length".to_string(),
-        OnionObject::Undefined(Some("Length of substring".to_string().into())).stabilize(),
+
+    // common_suffix 函数
+    let mut common_suffix_params = IndexMap::new();
+    common_suffix_params.insert(
+        "strings".to_string(),
+        OnionObject::Undefined(Some("Tuple of strings".to_string().into())).stabilize(),
     );
     module.insert(
-        "substr".to_string(),
+        "common_suffix".to_string(),
         wrap_native_function(
-            &build_named_dict(substr_params),
+            &build_named_dict(common_suffix_params),
             None,
             None,
-            "string::substr".to_string(),
-            &substr,
+            "string::common_suffix".to_string(),
+            &common_suffix,
         ),
     );
 
-    // index_of 函数
-    let mut index_of_params = IndexMap::new();
-    index_of_params.insert(
+    // split_any 函数
+    let mut split_any_params = IndexMap::new();
+    split_any_params.insert(
         "string".to_string(),
-        OnionObject::Undefined(Some("String to search in".to_string().into())).stabilize(),
+        OnionObject::Undefined(Some("String to split".to_string().into())).stabilize(),
     );
-    index_of_params.insert(
-        "substring".to_string(),
-        OnionObject::Undefined(Some("Substring to find".to_string().into())).stabilize(),
+    split_any_params.insert(
+        "delimiters".to_string(),
+        OnionObject::Undefined(Some(
+            "Characters that each act as a delimiter".to_string().into(),
+        ))
+        .stabilize(),
     );
     module.insert(
-        "index_of".to_string(),
+        "split_any".to_string(),
         wrap_native_function(
-            &build_named_dict(index_of_params),
+            &build_named_dict(split_any_params),
             None,
             None,
-            "string::index_of".to_string(),
-            &index_of,
+            "string::split_any".to_string(),
+            &split_any,
         ),
     );
 
-    // starts_with 函数
-    let mut starts_with_params = IndexMap::new();
-    starts_with_params.insert(
+    // word_count 函数
+    let mut word_count_params = IndexMap::new();
+    word_count_params.insert(
         "string".to_string(),
-        OnionObject::Undefined(Some("String to check".to_string().into())).stabilize(),
-    );
-    starts_with_params.insert(
-        "prefix".to_string(),
-        OnionObject::Undefined(Some("Prefix to check for".to_string().into())).stabilize(),
+        OnionObject::Undefined(Some("String to count words in".to_string().into())).stabilize(),
     );
     module.insert(
-        "starts_with".to_string(),
+        "word_count".to_string(),
         wrap_native_function(
-            &build_named_dict(starts_with_params),
+            &build_named_dict(word_count_params),
             None,
             None,
-            "string::starts_with".to_string(),
-            &starts_with,
+            "string::word_count".to_string(),
+            &word_count,
         ),
     );
 
-    // ends_with 函数
-    let mut ends_with_params = IndexMap::new();
-    ends_with_params.insert(
+    // words 函数
+    let mut words_params = IndexMap::new();
+    words_params.insert(
         "string".to_string(),
-        OnionObject::Undefined(Some("String to check".to_string().into())).stabilize(),
+        OnionObject::Undefined(Some("String to split into words".to_string().into())).stabilize(),
     );
-    ends_with_params.insert(
-        "suffix".to_string(),
-        OnionObject::Undefined(Some("Suffix to check for".to_string().into())).stabilize(),
+    module.insert(
+        "words".to_string(),
+        wrap_native_function(
+            &build_named_dict(words_params),
+            None,
+            None,
+            "string::words".to_string(),
+            &words,
+        ),
     );
+
+    // expand_tabs 函数
+    let mut expand_tabs_params = IndexMap::new();
+    expand_tabs_params.insert(
+        "string".to_string(),
+        OnionObject::Undefined(Some("String to expand".to_string().into())).stabilize(),
+    );
+    expand_tabs_params.insert("tab_size".to_string(), OnionObject::Integer(8).stabilize());
     module.insert(
-        "ends_with".to_string(),
+        "expand_tabs".to_string(),
         wrap_native_function(
-            &build_named_dict(ends_with_params),
+            &build_named_dict(expand_tabs_params),
             None,
             None,
-            "string::ends_with".to_string(),
-            &ends_with,
+            "string::expand_tabs".to_string(),
+            &expand_tabs,
         ),
     );
 
-    // repeat 函数
-    let mut repeat_params = IndexMap::new();
-    repeat_params.insert(
+    // mask 函数
+    let mut mask_params = IndexMap::new();
+    mask_params.insert(
         "string".to_string(),
-        OnionObject::Undefined(Some("String to repeat".to_string().into())).stabilize(),
+        OnionObject::Undefined(Some("String to redact".to_string().into())).stabilize(),
     );
-    repeat_params.insert(
-        "count".to_string(),
-        OnionObject::Undefined(Some("Number of times to repeat".to_string().into())).stabilize(),
+    mask_params.insert(
+        "visible_start".to_string(),
+        OnionObject::Integer(0).stabilize(),
+    );
+    mask_params.insert(
+        "visible_end".to_string(),
+        OnionObject::Integer(4).stabilize(),
+    );
+    mask_params.insert(
+        "mask_char".to_string(),
+        OnionObject::String("*".to_string().into()).stabilize(),
     );
     module.insert(
-        "repeat".to_string(),
+        "mask".to_string(),
         wrap_native_function(
-            &build_named_dict(repeat_params),
+            &build_named_dict(mask_params),
             None,
             None,
-            "string::repeat".to_string(),
-            &repeat,
+            "string::mask".to_string(),
+            &mask,
         ),
     );
 
-    // pad_left 函数
-    let mut pad_left_params = IndexMap::new();
-    pad_left_params.insert(
+    // display_width 函数
+    let mut display_width_params = IndexMap::new();
+    display_width_params.insert(
         "string".to_string(),
-        OnionObject::Undefined(Some("String to pad".to_string().into())).stabilize(),
+        OnionObject::Undefined(Some("String to measure".to_string().into())).stabilize(),
     );
-    pad_left_params.insert(
-        "length".to_string(),
-        OnionObject::Undefined(Some("Target length".to_string().into())).stabilize(),
+    module.insert(
+        "display_width".to_string(),
+        wrap_native_function(
+            &build_named_dict(display_width_params),
+            None,
+            None,
+            "string::display_width".to_string(),
+            &display_width,
+        ),
     );
-    pad_left_params.insert(
-        "pad_char".to_string(),
-        OnionObject::Undefined(Some("Character to pad with".to_string().into())).stabilize(),
+
+    // encode_uri_component 函数
+    let mut encode_uri_component_params = IndexMap::new();
+    encode_uri_component_params.insert(
+        "string".to_string(),
+        OnionObject::Undefined(Some("String to encode".to_string().into())).stabilize(),
     );
     module.insert(
-        "pad_left".to_string(),
+        "encode_uri_component".to_string(),
         wrap_native_function(
-            &build_named_dict(pad_left_params),
+            &build_named_dict(encode_uri_component_params),
             None,
             None,
-            "string::pad_left".to_string(),
-            &pad_left,
+            "string::encode_uri_component".to_string(),
+            &encode_uri_component,
         ),
     );
 
-    // pad_right 函数
-    let mut pad_right_params = IndexMap::new();
-    pad_right_params.insert(
+    // decode_uri_component 函数
+    let mut decode_uri_component_params = IndexMap::new();
+    decode_uri_component_params.insert(
         "string".to_string(),
-        OnionObject::Undefined(Some("String to pad".to_string().into())).stabilize(),
+        OnionObject::Undefined(Some("Percent-encoded string to decode".to_string().into()))
+            .stabilize(),
     );
-    pad_right_params.insert(
-        "length".to_string(),
-        OnionObject::Undefined(Some("Target length".to_string().into())).stabilize(),
+    module.insert(
+        "decode_uri_component".to_string(),
+        wrap_native_function(
+            &build_named_dict(decode_uri_component_params),
+            None,
+            None,
+            "string::decode_uri_component".to_string(),
+            &decode_uri_component,
+        ),
     );
-    pad_right_params.insert(
-        "pad_char".to_string(),
-        OnionObject::Undefined(Some("Character to pad with".to_string().into())).stabilize(),
+
+    // splitlines_keepends 函数
+    let mut splitlines_keepends_params = IndexMap::new();
+    splitlines_keepends_params.insert(
+        "string".to_string(),
+        OnionObject::Undefined(Some("String to split into lines".to_string().into())).stabilize(),
     );
     module.insert(
-        "pad_right".to_string(),
+        "splitlines_keepends".to_string(),
         wrap_native_function(
-            &build_named_dict(pad_right_params),
+            &build_named_dict(splitlines_keepends_params),
             None,
             None,
-            "string::pad_right".to_string(),
-            &pad_right,
+            "string::splitlines_keepends".to_string(),
+            &splitlines_keepends,
         ),
     );
 
-    // is_empty 函数
-    let mut is_empty_params = IndexMap::new();
-    is_empty_params.insert(
+    // longest_common_substring 函数
+    let mut longest_common_substring_params = IndexMap::new();
+    longest_common_substring_params.insert(
+        "a".to_string(),
+        OnionObject::Undefined(Some("First string".to_string().into())).stabilize(),
+    );
+    longest_common_substring_params.insert(
+        "b".to_string(),
+        OnionObject::Undefined(Some("Second string".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "longest_common_substring".to_string(),
+        wrap_native_function(
+            &build_named_dict(longest_common_substring_params),
+            None,
+            None,
+            "string::longest_common_substring".to_string(),
+            &longest_common_substring,
+        ),
+    );
+
+    // trim_chars 函数
+    let mut trim_chars_params = IndexMap::new();
+    trim_chars_params.insert(
         "string".to_string(),
-        OnionObject::Undefined(Some("String to check if empty".to_string().into())).stabilize(),
+        OnionObject::Undefined(Some("String to trim".to_string().into())).stabilize(),
+    );
+    trim_chars_params.insert(
+        "chars".to_string(),
+        OnionObject::Undefined(Some(
+            "Characters to remove from both ends".to_string().into(),
+        ))
+        .stabilize(),
     );
     module.insert(
-        "is_empty".to_string(),
+        "trim_chars".to_string(),
         wrap_native_function(
-            &build_named_dict(is_empty_params),
+            &build_named_dict(trim_chars_params),
             None,
             None,
-            "string::is_empty".to_string(),
-            &is_empty,
+            "string::trim_chars".to_string(),
+            &trim_chars,
         ),
     );
 
-    // reverse 函数
-    let mut reverse_params = IndexMap::new();
-    reverse_params.insert(
+    // justify 函数
+    let mut justify_params = IndexMap::new();
+    justify_params.insert(
         "string".to_string(),
-        OnionObject::Undefined(Some("String to reverse".to_string().into())).stabilize(),
+        OnionObject::Undefined(Some("String to justify".to_string().into())).stabilize(),
+    );
+    justify_params.insert(
+        "width".to_string(),
+        OnionObject::Undefined(Some("Target width in characters".to_string().into())).stabilize(),
+    );
+    justify_params.insert(
+        "mode".to_string(),
+        OnionObject::String("left".to_string().into()).stabilize(),
+    );
+    justify_params.insert(
+        "pad_char".to_string(),
+        OnionObject::String(" ".to_string().into()).stabilize(),
     );
     module.insert(
-        "reverse".to_string(),
+        "justify".to_string(),
         wrap_native_function(
-            &build_named_dict(reverse_params),
+            &build_named_dict(justify_params),
             None,
             None,
-            "string::reverse".to_string(),
-            &reverse,
+            "string::justify".to_string(),
+            &justify,
         ),
     );
 
@@ -1,21 +1,32 @@
 use std::{
+    collections::VecDeque,
+    fmt::Debug,
+    sync::{Arc, Mutex, MutexGuard},
     thread,
-    time::{Duration, SystemTime, UNIX_EPOCH},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
+use arc_gc::{
+    arc::{GCArc, GCArcWeak},
+    traceable::GCTraceable,
+};
 use indexmap::IndexMap;
 use onion_vm::{
     lambda::runnable::{Runnable, RuntimeError, StepResult},
     onion_tuple,
     types::{
         lambda::definition::{LambdaBody, OnionLambdaDefinition},
-        object::{OnionObject, OnionObjectCell, OnionStaticObject},
+        object::{OnionObject, OnionObjectCell, OnionObjectExt, OnionStaticObject},
+        pair::OnionPair,
         tuple::OnionTuple,
     },
     unwrap_step_result, GC,
 };
 
-use super::{build_named_dict, get_attr_direct, wrap_native_function};
+use super::{
+    build_named_dict, get_attr_direct, with_self_custom, wrap_native_function,
+    wrap_native_method_function, CallStep, LambdaCaller, LambdaCalling,
+};
 
 /// 获取当前时间戳（秒）
 fn timestamp(
@@ -297,6 +308,228 @@ fn async_sleep(
     ))
 }
 
+/// 调用 lambda 并测量耗时，返回 (result : elapsed_millis)
+#[derive(Clone, Default)]
+pub struct Measure {
+    start_time: Option<Instant>,
+}
+
+impl LambdaCalling for Measure {
+    const NAME: &'static str = "Measure";
+
+    fn resume(
+        &mut self,
+        argument: &OnionStaticObject,
+        result: Option<OnionStaticObject>,
+    ) -> Result<CallStep, RuntimeError> {
+        if let Some(result) = result {
+            let elapsed = self
+                .start_time
+                .map(|start| start.elapsed().as_secs_f64() * 1000.0)
+                .unwrap_or(0.0);
+            return Ok(CallStep::Return(OnionPair::new_static(
+                &result,
+                &OnionObject::Float(elapsed).stabilize(),
+            )));
+        }
+
+        let (func, arg) = argument.weak().with_data(|data| {
+            let func = get_attr_direct(data, "func".to_string())?;
+            let arg = get_attr_direct(data, "arg".to_string())?;
+            Ok((func, arg))
+        })?;
+        self.start_time = Some(Instant::now());
+        Ok(CallStep::Call(func, vec![arg]))
+    }
+}
+
+/// 将毫秒时长格式化为可读字符串，例如 "1h 2m 3s"，省略为零的部分
+fn format_duration(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    let millis = argument.weak().with_data(|data| {
+        get_attr_direct(data, "millis".to_string())?
+            .weak()
+            .to_integer()
+            .map_err(|e| RuntimeError::InvalidType(format!("Invalid duration: {}", e).into()))
+    })?;
+
+    if millis < 0 {
+        return Err(RuntimeError::DetailedError(
+            "Duration cannot be negative".to_string().into(),
+        ));
+    }
+
+    let units = [
+        ("d", 86_400_000),
+        ("h", 3_600_000),
+        ("m", 60_000),
+        ("s", 1_000),
+        ("ms", 1),
+    ];
+    let mut remaining = millis;
+    let mut parts = vec![];
+    for (suffix, size) in units {
+        let count = remaining / size;
+        remaining %= size;
+        if count > 0 {
+            parts.push(format!("{}{}", count, suffix));
+        }
+    }
+    let formatted = if parts.is_empty() {
+        "0ms".to_string()
+    } else {
+        parts.join(" ")
+    };
+    Ok(OnionObject::String(formatted.into()).stabilize())
+}
+
+/// 计算两个时间戳（秒）之间相差的整天数（绝对值）
+fn days_between(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    let (a, b) = argument.weak().with_data(|data| {
+        let a = get_attr_direct(data, "timestamp_a".to_string())?
+            .weak()
+            .to_integer()
+            .map_err(|e| RuntimeError::InvalidType(format!("Invalid timestamp_a: {}", e).into()))?;
+
+        let b = get_attr_direct(data, "timestamp_b".to_string())?
+            .weak()
+            .to_integer()
+            .map_err(|e| RuntimeError::InvalidType(format!("Invalid timestamp_b: {}", e).into()))?;
+
+        Ok((a, b))
+    })?;
+
+    let days = (a.abs_diff(b) / 86400) as i64;
+    Ok(OnionObject::Integer(days).stabilize())
+}
+
+/// 令牌桶限流器，状态为（上次补充时间，剩余令牌数），由包装对象的所有副本共享
+/// 桶容量为 max(rate, 1)，否则小于 1 的速率永远攒不够一个令牌
+pub struct OnionRateLimiter {
+    rate: f64,
+    state: Arc<Mutex<(Instant, f64)>>,
+}
+
+impl OnionRateLimiter {
+    fn lock(&self) -> Result<MutexGuard<'_, (Instant, f64)>, RuntimeError> {
+        self.state.lock().map_err(|_| {
+            RuntimeError::DetailedError("rate_limiter lock is poisoned".to_string().into())
+        })
+    }
+}
+
+impl Debug for OnionRateLimiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "OnionRateLimiter({} per second)", self.rate)
+    }
+}
+
+impl GCTraceable<OnionObjectCell> for OnionRateLimiter {
+    fn collect(&self, _: &mut VecDeque<GCArcWeak<OnionObjectCell>>) {}
+}
+
+impl OnionObjectExt for OnionRateLimiter {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn upgrade(&self, _: &mut Vec<GCArc<OnionObjectCell>>) {
+        // nothing
+    }
+
+    fn equals(&self, other: &OnionObject) -> Result<bool, RuntimeError> {
+        self.is_same(other)
+    }
+
+    fn is_same(&self, other: &OnionObject) -> Result<bool, RuntimeError> {
+        match other {
+            OnionObject::Custom(custom) => Ok(custom
+                .as_any()
+                .downcast_ref::<OnionRateLimiter>()
+                .is_some_and(|other| Arc::ptr_eq(&self.state, &other.state))),
+            _ => Ok(false),
+        }
+    }
+
+    fn type_of(&self) -> Result<String, RuntimeError> {
+        Ok("RateLimiter".to_string())
+    }
+
+    fn repr(&self, ptrs: &Vec<*const OnionObject>) -> Result<String, RuntimeError> {
+        self.to_string(ptrs)
+    }
+
+    fn to_string(&self, _: &Vec<*const OnionObject>) -> Result<String, RuntimeError> {
+        Ok(format!("RateLimiter({} per second)", self.rate))
+    }
+}
+
+/// 按经过的时间补充令牌（不超过桶容量），有可用令牌时消耗一个并返回 true
+fn rate_limiter_try_acquire(
+    self_object: Option<&OnionStaticObject>,
+    _argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    with_self_custom(self_object, |limiter: &OnionRateLimiter| {
+        let mut state = limiter.lock()?;
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.0).as_secs_f64();
+        state.0 = now;
+        state.1 = (state.1 + elapsed * limiter.rate).min(limiter.rate.max(1.0));
+        let acquired = state.1 >= 1.0;
+        if acquired {
+            state.1 -= 1.0;
+        }
+        Ok(OnionObject::Boolean(acquired).stabilize())
+    })
+}
+
+/// 创建每秒最多放行 max_per_second 次的令牌桶限流器，初始时桶是满的
+fn rate_limiter(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    let rate = argument.weak().with_data(|data| {
+        get_attr_direct(data, "max_per_second".to_string())?
+            .weak()
+            .to_float()
+            .map_err(|e| RuntimeError::InvalidType(format!("Invalid max_per_second: {}", e).into()))
+    })?;
+
+    if !rate.is_finite() || rate <= 0.0 {
+        return Err(RuntimeError::InvalidOperation(
+            "rate_limiter requires a positive max_per_second"
+                .to_string()
+                .into(),
+        ));
+    }
+
+    let handle = OnionObject::Custom(Arc::new(OnionRateLimiter {
+        rate,
+        state: Arc::new(Mutex::new((Instant::now(), rate.max(1.0)))),
+    }))
+    .stabilize();
+
+    let mut object = IndexMap::new();
+    object.insert(
+        "try_acquire".to_string(),
+        wrap_native_method_function(
+            &onion_tuple!(),
+            None,
+            Some(&handle),
+            "time::rate_limiter::try_acquire".to_string(),
+            &rate_limiter_try_acquire,
+        ),
+    );
+    object.insert("handle".to_string(), handle);
+    Ok(build_named_dict(object))
+}
+
 /// 构建时间模块
 pub fn build_module() -> OnionStaticObject {
     let mut module = IndexMap::new(); // timestamp 函数 - 获取当前时间戳（秒）
@@ -433,5 +666,79 @@ pub fn build_module() -> OnionStaticObject {
         ),
     );
 
+    // measure 函数 - 测量 lambda 调用耗时
+    let mut measure_params = IndexMap::new();
+    measure_params.insert(
+        "func".to_string(),
+        OnionObject::Undefined(Some("Function to measure".to_string().into())).stabilize(),
+    );
+    measure_params.insert(
+        "arg".to_string(),
+        OnionObject::Undefined(Some("Argument passed to the function".to_string().into()))
+            .stabilize(),
+    );
+    module.insert(
+        "measure".to_string(),
+        OnionLambdaDefinition::new_static(
+            &build_named_dict(measure_params),
+            LambdaBody::NativeFunction(Box::new(LambdaCaller::new(Measure::default()))),
+            None,
+            None,
+            "time::measure".to_string(),
+        ),
+    );
+
+    // format_duration 函数 - 格式化毫秒时长
+    let mut format_duration_params = IndexMap::new();
+    format_duration_params.insert("millis".to_string(), OnionObject::Integer(0).stabilize());
+    module.insert(
+        "format_duration".to_string(),
+        wrap_native_function(
+            &build_named_dict(format_duration_params),
+            None,
+            None,
+            "time::format_duration".to_string(),
+            &format_duration,
+        ),
+    );
+
+    // days_between 函数 - 计算两个时间戳之间的整天数
+    let mut days_between_params = IndexMap::new();
+    days_between_params.insert(
+        "timestamp_a".to_string(),
+        OnionObject::Integer(0).stabilize(),
+    );
+    days_between_params.insert(
+        "timestamp_b".to_string(),
+        OnionObject::Integer(0).stabilize(),
+    );
+    module.insert(
+        "days_between".to_string(),
+        wrap_native_function(
+            &build_named_dict(days_between_params),
+            None,
+            None,
+            "time::days_between".to_string(),
+            &days_between,
+        ),
+    );
+
+    // rate_limiter 函数 - 创建令牌桶限流器
+    let mut rate_limiter_params = IndexMap::new();
+    rate_limiter_params.insert(
+        "max_per_second".to_string(),
+        OnionObject::Undefined(Some("Tokens refilled per second".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "rate_limiter".to_string(),
+        wrap_native_function(
+            &build_named_dict(rate_limiter_params),
+            None,
+            None,
+            "time::rate_limiter".to_string(),
+            &rate_limiter,
+        ),
+    );
+
     build_named_dict(module)
 }
@@ -2,13 +2,17 @@ use indexmap::IndexMap;
 use onion_vm::{
     lambda::runnable::RuntimeError,
     types::{
+        lambda::definition::{LambdaBody, OnionLambdaDefinition},
         object::{OnionObject, OnionObjectCell, OnionStaticObject},
         tuple::OnionTuple,
     },
     GC,
 };
 
-use super::{build_named_dict, get_attr_direct, wrap_native_function};
+use super::{
+    build_named_dict, get_attr_direct, math::Accumulate, wrap_native_function, CallStep,
+    LambdaCaller, LambdaCalling, MAX_GENERATED_LEN,
+};
 
 fn push(
     argument: &OnionStaticObject,
@@ -120,6 +124,432 @@ fn remove(
     })
 }
 
+fn transpose(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let matrix = get_attr_direct(data, "matrix".to_string())?;
+        matrix.weak().with_data(|matrix| match matrix {
+            OnionObject::Tuple(rows) => {
+                let mut columns: Vec<Vec<OnionObject>> = vec![];
+                for (row_index, row) in rows.get_elements().iter().enumerate() {
+                    let row = match row {
+                        OnionObject::Tuple(row) => row.get_elements(),
+                        _ => {
+                            return Err(RuntimeError::InvalidOperation(
+                                "Expected a tuple of tuples for 'matrix'".to_string().into(),
+                            ))
+                        }
+                    };
+                    if row_index == 0 {
+                        columns = vec![vec![]; row.len()];
+                    } else if row.len() != columns.len() {
+                        return Err(RuntimeError::InvalidOperation(
+                            format!(
+                                "Row {} has length {}, expected {}",
+                                row_index,
+                                row.len(),
+                                columns.len()
+                            )
+                            .into(),
+                        ));
+                    }
+                    for (column, value) in columns.iter_mut().zip(row.iter()) {
+                        column.push(value.clone());
+                    }
+                }
+                let transposed = columns
+                    .into_iter()
+                    .map(|column| OnionObject::Tuple(OnionTuple::new(column).into()))
+                    .collect();
+                Ok(OnionObject::Tuple(OnionTuple::new(transposed).into()).stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "Expected a tuple for 'matrix'".to_string().into(),
+            )),
+        })
+    })
+}
+
+fn window(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let tuple = get_attr_direct(data, "container".to_string())?;
+        let size = get_attr_direct(data, "size".to_string())?;
+        let size = match size.weak() {
+            OnionObject::Integer(size) if *size > 0 => *size as usize,
+            OnionObject::Integer(_) => {
+                return Err(RuntimeError::InvalidOperation(
+                    "Window size must be positive".to_string().into(),
+                ))
+            }
+            _ => {
+                return Err(RuntimeError::InvalidOperation(
+                    "Window size must be an integer".to_string().into(),
+                ))
+            }
+        };
+        tuple.weak().with_data(|tuple| match tuple {
+            OnionObject::Tuple(tuple) => {
+                let windows = tuple
+                    .get_elements()
+                    .windows(size)
+                    .map(|window| OnionObject::Tuple(OnionTuple::new(window.to_vec()).into()))
+                    .collect();
+                Ok(OnionObject::Tuple(OnionTuple::new(windows).into()).stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "Expected a tuple for 'container'".to_string().into(),
+            )),
+        })
+    })
+}
+
+/// Stabilized elements of a `container` argument
+fn tuple_elements(container: &OnionStaticObject) -> Result<Vec<OnionStaticObject>, RuntimeError> {
+    container.weak().with_data(|container| match container {
+        OnionObject::Tuple(tuple) => Ok(tuple
+            .get_elements()
+            .iter()
+            .map(|element| element.stabilize())
+            .collect()),
+        _ => Err(RuntimeError::InvalidOperation(
+            "Expected a tuple for 'container'".to_string().into(),
+        )),
+    })
+}
+
+#[derive(Clone)]
+pub struct FlatMap {
+    func: Option<OnionStaticObject>,
+    elements: Vec<OnionStaticObject>,
+    index: usize,
+    result: Vec<OnionStaticObject>,
+}
+
+impl Default for FlatMap {
+    fn default() -> Self {
+        FlatMap {
+            func: None,
+            elements: vec![],
+            index: 0,
+            result: vec![],
+        }
+    }
+}
+
+impl LambdaCalling for FlatMap {
+    const NAME: &'static str = "FlatMap";
+
+    fn resume(
+        &mut self,
+        argument: &OnionStaticObject,
+        result: Option<OnionStaticObject>,
+    ) -> Result<CallStep, RuntimeError> {
+        if let Some(result) = result {
+            result.weak().with_data(|data| match data {
+                OnionObject::Tuple(tuple) => {
+                    self.result.extend(
+                        tuple
+                            .get_elements()
+                            .iter()
+                            .map(|element| element.stabilize()),
+                    );
+                    Ok(())
+                }
+                _ => Err(RuntimeError::InvalidOperation(
+                    format!(
+                        "flat_map function returned a non-tuple for element {}",
+                        self.index - 1
+                    )
+                    .into(),
+                )),
+            })?;
+        }
+        if self.func.is_none() {
+            let (container, func) = argument.weak().with_data(|data| {
+                let container = get_attr_direct(data, "container".to_string())?;
+                let func = get_attr_direct(data, "func".to_string())?;
+                Ok((container, func))
+            })?;
+            self.elements = tuple_elements(&container)?;
+            self.func = Some(func);
+        }
+
+        match (&self.func, self.elements.get(self.index)) {
+            (Some(func), Some(element)) => {
+                self.index += 1;
+                Ok(CallStep::Call(func.clone(), vec![element.clone()]))
+            }
+            _ => Ok(CallStep::Return(OnionTuple::new_static_no_ref(
+                &self.result,
+            ))),
+        }
+    }
+
+    fn context(&self) -> serde_json::Map<String, serde_json::Value> {
+        let mut context = serde_json::Map::new();
+        context.insert("index".to_string(), self.index.into());
+        context
+    }
+}
+
+fn repeat(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let tuple = get_attr_direct(data, "container".to_string())?;
+        let count = get_attr_direct(data, "count".to_string())?;
+        let count = match count.weak() {
+            OnionObject::Integer(count) if *count >= 0 => *count as usize,
+            OnionObject::Integer(_) => {
+                return Err(RuntimeError::InvalidOperation(
+                    "Repeat count cannot be negative".to_string().into(),
+                ))
+            }
+            _ => {
+                return Err(RuntimeError::InvalidOperation(
+                    "Repeat count must be an integer".to_string().into(),
+                ))
+            }
+        };
+        tuple.weak().with_data(|tuple| match tuple {
+            OnionObject::Tuple(tuple) => {
+                let elements = tuple.get_elements();
+                let total = elements
+                    .len()
+                    .checked_mul(count)
+                    .filter(|total| *total <= MAX_GENERATED_LEN)
+                    .ok_or_else(|| {
+                        RuntimeError::InvalidOperation(
+                            "Repeated tuple would be too large".to_string().into(),
+                        )
+                    })?;
+                let mut repeated = Vec::with_capacity(total);
+                for _ in 0..count {
+                    repeated.extend(elements.iter().cloned());
+                }
+                Ok(OnionObject::Tuple(OnionTuple::new(repeated).into()).stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "Expected a tuple for 'container'".to_string().into(),
+            )),
+        })
+    })
+}
+
+fn intersperse(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let tuple = get_attr_direct(data, "container".to_string())?;
+        let sep = get_attr_direct(data, "sep".to_string())?;
+        tuple.weak().with_data(|tuple| match tuple {
+            OnionObject::Tuple(tuple) => {
+                let elements = tuple.get_elements();
+                let mut result = Vec::with_capacity((elements.len() * 2).saturating_sub(1));
+                for (index, element) in elements.iter().enumerate() {
+                    if index > 0 {
+                        result.push(sep.weak().clone());
+                    }
+                    result.push(element.clone());
+                }
+                Ok(OnionObject::Tuple(OnionTuple::new(result).into()).stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "Expected a tuple for 'container'".to_string().into(),
+            )),
+        })
+    })
+}
+
+fn dedup_consecutive(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let tuple = get_attr_direct(data, "container".to_string())?;
+        tuple.weak().with_data(|tuple| match tuple {
+            OnionObject::Tuple(tuple) => {
+                let mut result: Vec<OnionObject> = vec![];
+                for element in tuple.get_elements() {
+                    if let Some(last) = result.last() {
+                        if last.equals(element)? {
+                            continue;
+                        }
+                    }
+                    result.push(element.clone());
+                }
+                Ok(OnionObject::Tuple(OnionTuple::new(result).into()).stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "Expected a tuple for 'container'".to_string().into(),
+            )),
+        })
+    })
+}
+
+fn rotate(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let tuple = get_attr_direct(data, "container".to_string())?;
+        let n = get_attr_direct(data, "n".to_string())?;
+        let n = match n.weak() {
+            OnionObject::Integer(n) => *n,
+            _ => {
+                return Err(RuntimeError::InvalidOperation(
+                    "Rotation amount must be an integer".to_string().into(),
+                ))
+            }
+        };
+        tuple.weak().with_data(|tuple| match tuple {
+            OnionObject::Tuple(tuple) => {
+                let mut rotated = tuple.get_elements().clone();
+                if !rotated.is_empty() {
+                    let shift = n.rem_euclid(rotated.len() as i64) as usize;
+                    rotated.rotate_left(shift);
+                }
+                Ok(OnionObject::Tuple(OnionTuple::new(rotated).into()).stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "Expected a tuple for 'container'".to_string().into(),
+            )),
+        })
+    })
+}
+
+fn extreme(
+    argument: &OnionStaticObject,
+    want_max: bool,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let tuple = get_attr_direct(data, "container".to_string())?;
+        tuple.weak().with_data(|tuple| match tuple {
+            OnionObject::Tuple(tuple) => {
+                let mut elements = tuple.get_elements().iter();
+                let mut best = elements.next().ok_or_else(|| {
+                    RuntimeError::InvalidOperation(
+                        "Cannot take the extreme of an empty tuple"
+                            .to_string()
+                            .into(),
+                    )
+                })?;
+                for element in elements {
+                    let better = if want_max {
+                        best.binary_lt(element)?
+                    } else {
+                        element.binary_lt(best)?
+                    };
+                    if better {
+                        best = element;
+                    }
+                }
+                Ok(best.stabilize())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "Expected a tuple for 'container'".to_string().into(),
+            )),
+        })
+    })
+}
+
+fn min(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    extreme(argument, false)
+}
+
+fn max(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    extreme(argument, true)
+}
+
+#[derive(Clone)]
+pub struct ExtremeBy {
+    func: Option<OnionStaticObject>,
+    elements: Vec<OnionStaticObject>,
+    keys: Vec<OnionStaticObject>,
+    want_max: bool,
+}
+
+impl ExtremeBy {
+    fn new(want_max: bool) -> Self {
+        ExtremeBy {
+            func: None,
+            elements: vec![],
+            keys: vec![],
+            want_max,
+        }
+    }
+
+    fn best_index(&self) -> Result<usize, RuntimeError> {
+        let mut best = 0;
+        for (index, key) in self.keys.iter().enumerate().skip(1) {
+            let better = if self.want_max {
+                self.keys[best].weak().binary_lt(key.weak())?
+            } else {
+                key.weak().binary_lt(self.keys[best].weak())?
+            };
+            if better {
+                best = index;
+            }
+        }
+        Ok(best)
+    }
+}
+
+impl LambdaCalling for ExtremeBy {
+    const NAME: &'static str = "ExtremeBy";
+
+    fn resume(
+        &mut self,
+        argument: &OnionStaticObject,
+        result: Option<OnionStaticObject>,
+    ) -> Result<CallStep, RuntimeError> {
+        if let Some(key) = result {
+            self.keys.push(key);
+        }
+        if self.func.is_none() {
+            let (container, func) = argument.weak().with_data(|data| {
+                let container = get_attr_direct(data, "container".to_string())?;
+                let func = get_attr_direct(data, "func".to_string())?;
+                Ok((container, func))
+            })?;
+            self.elements = tuple_elements(&container)?;
+            if self.elements.is_empty() {
+                return Err(RuntimeError::InvalidOperation(
+                    "Cannot take the extreme of an empty tuple"
+                        .to_string()
+                        .into(),
+                ));
+            }
+            self.func = Some(func);
+        }
+
+        match (&self.func, self.elements.get(self.keys.len())) {
+            (Some(func), Some(element)) => Ok(CallStep::Call(func.clone(), vec![element.clone()])),
+            _ => Ok(CallStep::Return(self.elements[self.best_index()?].clone())),
+        }
+    }
+
+    fn context(&self) -> serde_json::Map<String, serde_json::Value> {
+        let mut context = serde_json::Map::new();
+        context.insert("index".to_string(), self.keys.len().into());
+        context.insert("want_max".to_string(), self.want_max.into());
+        context
+    }
+}
+
 /// Build the type conversion module
 pub fn build_module() -> OnionStaticObject {
     let mut module = IndexMap::new();
@@ -205,5 +635,260 @@ pub fn build_module() -> OnionStaticObject {
         ),
     );
 
+    let mut transpose_params = IndexMap::new();
+    transpose_params.insert(
+        "matrix".to_string(),
+        OnionObject::Undefined(Some("Tuple of equal-length tuples".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "transpose".to_string(),
+        wrap_native_function(
+            &build_named_dict(transpose_params),
+            None,
+            None,
+            "tuple::transpose".to_string(),
+            &transpose,
+        ),
+    );
+
+    let mut window_params = IndexMap::new();
+    window_params.insert(
+        "container".to_string(),
+        OnionObject::Undefined(Some("Container tuple".to_string().into())).stabilize(),
+    );
+    window_params.insert(
+        "size".to_string(),
+        OnionObject::Undefined(Some("Window length".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "window".to_string(),
+        wrap_native_function(
+            &build_named_dict(window_params),
+            None,
+            None,
+            "tuple::window".to_string(),
+            &window,
+        ),
+    );
+
+    let mut flat_map_params = IndexMap::new();
+    flat_map_params.insert(
+        "container".to_string(),
+        OnionObject::Undefined(Some("Container tuple".to_string().into())).stabilize(),
+    );
+    flat_map_params.insert(
+        "func".to_string(),
+        OnionObject::Undefined(Some(
+            "Function returning a tuple for each element"
+                .to_string()
+                .into(),
+        ))
+        .stabilize(),
+    );
+    module.insert(
+        "flat_map".to_string(),
+        OnionLambdaDefinition::new_static(
+            &build_named_dict(flat_map_params),
+            LambdaBody::NativeFunction(Box::new(LambdaCaller::new(FlatMap::default()))),
+            None,
+            None,
+            "tuple::flat_map".to_string(),
+        ),
+    );
+
+    let mut repeat_params = IndexMap::new();
+    repeat_params.insert(
+        "container".to_string(),
+        OnionObject::Undefined(Some("Container tuple".to_string().into())).stabilize(),
+    );
+    repeat_params.insert(
+        "count".to_string(),
+        OnionObject::Undefined(Some("Number of repetitions".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "repeat".to_string(),
+        wrap_native_function(
+            &build_named_dict(repeat_params),
+            None,
+            None,
+            "tuple::repeat".to_string(),
+            &repeat,
+        ),
+    );
+
+    let mut intersperse_params = IndexMap::new();
+    intersperse_params.insert(
+        "container".to_string(),
+        OnionObject::Undefined(Some("Container tuple".to_string().into())).stabilize(),
+    );
+    intersperse_params.insert(
+        "sep".to_string(),
+        OnionObject::Undefined(Some(
+            "Separator inserted between elements".to_string().into(),
+        ))
+        .stabilize(),
+    );
+    module.insert(
+        "intersperse".to_string(),
+        wrap_native_function(
+            &build_named_dict(intersperse_params),
+            None,
+            None,
+            "tuple::intersperse".to_string(),
+            &intersperse,
+        ),
+    );
+
+    let mut dedup_consecutive_params = IndexMap::new();
+    dedup_consecutive_params.insert(
+        "container".to_string(),
+        OnionObject::Undefined(Some("Container tuple".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "dedup_consecutive".to_string(),
+        wrap_native_function(
+            &build_named_dict(dedup_consecutive_params),
+            None,
+            None,
+            "tuple::dedup_consecutive".to_string(),
+            &dedup_consecutive,
+        ),
+    );
+
+    let mut scan_params = IndexMap::new();
+    scan_params.insert(
+        "container".to_string(),
+        OnionObject::Undefined(Some("Container tuple".to_string().into())).stabilize(),
+    );
+    scan_params.insert(
+        "func".to_string(),
+        OnionObject::Undefined(Some(
+            "Binary function (state, element) -> state"
+                .to_string()
+                .into(),
+        ))
+        .stabilize(),
+    );
+    scan_params.insert(
+        "initial".to_string(),
+        OnionObject::Undefined(Some("Initial state".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "scan".to_string(),
+        OnionLambdaDefinition::new_static(
+            &build_named_dict(scan_params),
+            LambdaBody::NativeFunction(Box::new(LambdaCaller::new(Accumulate::new(
+                "container",
+                true,
+            )))),
+            None,
+            None,
+            "tuple::scan".to_string(),
+        ),
+    );
+
+    let mut rotate_params = IndexMap::new();
+    rotate_params.insert(
+        "container".to_string(),
+        OnionObject::Undefined(Some("Container tuple".to_string().into())).stabilize(),
+    );
+    rotate_params.insert(
+        "n".to_string(),
+        OnionObject::Undefined(Some(
+            "Positions to rotate left, negative rotates right"
+                .to_string()
+                .into(),
+        ))
+        .stabilize(),
+    );
+    module.insert(
+        "rotate".to_string(),
+        wrap_native_function(
+            &build_named_dict(rotate_params),
+            None,
+            None,
+            "tuple::rotate".to_string(),
+            &rotate,
+        ),
+    );
+
+    let mut min_params = IndexMap::new();
+    min_params.insert(
+        "container".to_string(),
+        OnionObject::Undefined(Some("Container tuple".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "min".to_string(),
+        wrap_native_function(
+            &build_named_dict(min_params),
+            None,
+            None,
+            "tuple::min".to_string(),
+            &min,
+        ),
+    );
+
+    let mut max_params = IndexMap::new();
+    max_params.insert(
+        "container".to_string(),
+        OnionObject::Undefined(Some("Container tuple".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "max".to_string(),
+        wrap_native_function(
+            &build_named_dict(max_params),
+            None,
+            None,
+            "tuple::max".to_string(),
+            &max,
+        ),
+    );
+
+    let mut min_by_params = IndexMap::new();
+    min_by_params.insert(
+        "container".to_string(),
+        OnionObject::Undefined(Some("Container tuple".to_string().into())).stabilize(),
+    );
+    min_by_params.insert(
+        "func".to_string(),
+        OnionObject::Undefined(Some(
+            "Key function applied to each element".to_string().into(),
+        ))
+        .stabilize(),
+    );
+    module.insert(
+        "min_by".to_string(),
+        OnionLambdaDefinition::new_static(
+            &build_named_dict(min_by_params),
+            LambdaBody::NativeFunction(Box::new(LambdaCaller::new(ExtremeBy::new(false)))),
+            None,
+            None,
+            "tuple::min_by".to_string(),
+        ),
+    );
+
+    let mut max_by_params = IndexMap::new();
+    max_by_params.insert(
+        "container".to_string(),
+        OnionObject::Undefined(Some("Container tuple".to_string().into())).stabilize(),
+    );
+    max_by_params.insert(
+        "func".to_string(),
+        OnionObject::Undefined(Some(
+            "Key function applied to each element".to_string().into(),
+        ))
+        .stabilize(),
+    );
+    module.insert(
+        "max_by".to_string(),
+        OnionLambdaDefinition::new_static(
+            &build_named_dict(max_by_params),
+            LambdaBody::NativeFunction(Box::new(LambdaCaller::new(ExtremeBy::new(true)))),
+            None,
+            None,
+            "tuple::max_by".to_string(),
+        ),
+    );
+
     build_named_dict(module)
 }
@@ -1,13 +1,26 @@
-use std::vec;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    vec,
+};
 
 use indexmap::IndexMap;
 use onion_vm::{
-    lambda::runnable::RuntimeError,
-    types::object::{OnionObject, OnionObjectCell, OnionStaticObject},
+    lambda::runnable::{Runnable, RuntimeError, StepResult},
+    onion_tuple,
+    types::{
+        lambda::definition::{LambdaBody, OnionLambdaDefinition},
+        named::OnionNamed,
+        object::{OnionObject, OnionObjectCell, OnionStaticObject},
+        tuple::OnionTuple,
+    },
     GC,
 };
 
-use super::{build_named_dict, get_attr_direct, tuple, wrap_native_function};
+use super::{
+    build_named_dict, get_attr_direct, tuple, wrap_native_function, CallStep, LambdaCaller,
+    LambdaCalling,
+};
 
 /// Convert object to string
 fn to_string(
@@ -252,6 +265,850 @@ fn find(
     })
 }
 
+/// Validate a value against a schema of field name to type name
+fn validate(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let value = get_attr_direct(data, "value".to_string())?;
+        let schema = get_attr_direct(data, "schema".to_string())?;
+
+        schema.weak().with_data(|schema_data| match schema_data {
+            OnionObject::Tuple(fields) => {
+                let mut problems = vec![];
+                for field in fields.get_elements() {
+                    let name = field.key_of()?;
+                    let expected = field.value_of()?.weak().to_string(&vec![])?;
+                    let field_name = name.weak().to_string(&vec![])?;
+                    match value
+                        .weak()
+                        .with_attribute(name.weak(), &|obj| Ok(obj.stabilize()))
+                    {
+                        Ok(found) => {
+                            let actual = found.weak().type_of()?;
+                            if actual != expected {
+                                problems.push(
+                                    OnionObject::String(
+                                        format!(
+                                            "field '{}' expected {}, got {}",
+                                            field_name, expected, actual
+                                        )
+                                        .into(),
+                                    )
+                                    .stabilize(),
+                                );
+                            }
+                        }
+                        Err(RuntimeError::InvalidOperation(_)) => {
+                            problems.push(
+                                OnionObject::String(
+                                    format!("missing field '{}'", field_name).into(),
+                                )
+                                .stabilize(),
+                            );
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+                Ok(OnionTuple::new_static_no_ref(&problems))
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "validate requires a schema tuple".to_string().into(),
+            )),
+        })
+    })
+}
+
+/// Build an enum-like mapping from names to their ordinal
+fn enum_from(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let names = get_attr_direct(data, "names".to_string())?;
+
+        names.weak().with_data(|names_data| match names_data {
+            OnionObject::Tuple(tuple) => {
+                let mut mapping = IndexMap::new();
+                for (index, name) in tuple.get_elements().iter().enumerate() {
+                    let name = match name {
+                        OnionObject::String(s) => s.as_ref().clone(),
+                        _ => {
+                            return Err(RuntimeError::InvalidOperation(
+                                "enum_from requires a tuple of strings".to_string().into(),
+                            ))
+                        }
+                    };
+                    if mapping.contains_key(&name) {
+                        return Err(RuntimeError::InvalidOperation(
+                            format!("Duplicate enum name '{}'", name).into(),
+                        ));
+                    }
+                    mapping.insert(name, OnionObject::Integer(index as i64).stabilize());
+                }
+                Ok(build_named_dict(mapping))
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "enum_from requires a tuple of strings".to_string().into(),
+            )),
+        })
+    })
+}
+
+/// Lambda body that caches results of `func` keyed by the argument's type and repr
+#[derive(Clone)]
+pub struct Memoized {
+    func: OnionStaticObject,
+    cache: Arc<Mutex<HashMap<String, OnionStaticObject>>>,
+    key: Option<String>,
+}
+
+impl Memoized {
+    fn new(func: OnionStaticObject) -> Self {
+        Memoized {
+            func,
+            cache: Arc::new(Mutex::new(HashMap::new())),
+            key: None,
+        }
+    }
+}
+
+impl LambdaCalling for Memoized {
+    const NAME: &'static str = "Memoized";
+
+    fn resume(
+        &mut self,
+        argument: &OnionStaticObject,
+        result: Option<OnionStaticObject>,
+    ) -> Result<CallStep, RuntimeError> {
+        if let Some(result) = result {
+            if let (Some(key), Ok(mut cache)) = (self.key.take(), self.cache.lock()) {
+                cache.insert(key, result.clone());
+            }
+            return Ok(CallStep::Return(result));
+        }
+
+        let arg = argument
+            .weak()
+            .with_data(|data| get_attr_direct(data, "arg".to_string()))?;
+        // repr plus the type name keeps 3, 3.0 and "3" from sharing a cache entry
+        let key = arg
+            .weak()
+            .with_data(|data| Ok(format!("{}:{}", data.type_of()?, data.repr(&vec![])?)))?;
+        let cached = self
+            .cache
+            .lock()
+            .map_err(|_| {
+                RuntimeError::DetailedError("memoize cache is poisoned".to_string().into())
+            })?
+            .get(&key)
+            .cloned();
+        if let Some(cached) = cached {
+            return Ok(CallStep::Return(cached));
+        }
+        self.key = Some(key);
+        Ok(CallStep::Call(self.func.clone(), vec![arg]))
+    }
+
+    fn context(&self) -> serde_json::Map<String, serde_json::Value> {
+        let cached = self.cache.lock().map(|cache| cache.len()).unwrap_or(0);
+        let mut context = serde_json::Map::new();
+        context.insert("cached".to_string(), cached.into());
+        context
+    }
+}
+
+/// Wrap a lambda so results are cached per distinct argument
+fn memoize(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let func = get_attr_direct(data, "func".to_string())?;
+        let mut params = IndexMap::new();
+        params.insert(
+            "arg".to_string(),
+            OnionObject::Undefined(Some("Argument passed to the function".to_string().into()))
+                .stabilize(),
+        );
+        Ok(OnionLambdaDefinition::new_static(
+            &build_named_dict(params),
+            LambdaBody::NativeFunction(Box::new(LambdaCaller::new(Memoized::new(func)))),
+            None,
+            None,
+            "types::memoized".to_string(),
+        ))
+    })
+}
+
+/// Render a value as an indented tree, one element per line
+pub fn pretty_string(
+    obj: &OnionObject,
+    indent: usize,
+    depth: usize,
+) -> Result<String, RuntimeError> {
+    match obj {
+        OnionObject::Tuple(tuple) => {
+            let elements = tuple.get_elements();
+            if elements.is_empty() {
+                return Ok("()".to_string());
+            }
+            let inner = " ".repeat(indent * (depth + 1));
+            let mut result = "(\n".to_string();
+            for element in elements {
+                result.push_str(&inner);
+                result.push_str(
+                    &element.with_data(|element| pretty_string(element, indent, depth + 1))?,
+                );
+                result.push_str(",\n");
+            }
+            result.push_str(&" ".repeat(indent * depth));
+            result.push(')');
+            Ok(result)
+        }
+        OnionObject::Pair(_) | OnionObject::Named(_) => {
+            let separator = if matches!(obj, OnionObject::Pair(_)) {
+                " : "
+            } else {
+                " => "
+            };
+            let key = obj.key_of()?;
+            let value = obj.value_of()?;
+            Ok(format!(
+                "{}{}{}",
+                key.weak()
+                    .with_data(|key_data| pretty_string(key_data, indent, depth))?,
+                separator,
+                value
+                    .weak()
+                    .with_data(|value_data| pretty_string(value_data, indent, depth))?,
+            ))
+        }
+        OnionObject::String(s) => Ok(format!("{:?}", s.as_ref())),
+        _ => obj.to_string(&vec![]),
+    }
+}
+
+/// Render a nested value as a multi-line indented string
+fn pretty(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let value = get_attr_direct(data, "value".to_string())?;
+        let indent = get_attr_direct(data, "indent".to_string())?;
+        let indent = match indent.weak() {
+            OnionObject::Integer(n) if *n >= 0 => *n as usize,
+            _ => {
+                return Err(RuntimeError::InvalidOperation(
+                    "pretty requires a non-negative integer indent"
+                        .to_string()
+                        .into(),
+                ))
+            }
+        };
+        let rendered = value
+            .weak()
+            .with_data(|value_data| pretty_string(value_data, indent, 0))?;
+        Ok(OnionObject::String(rendered.into()).stabilize())
+    })
+}
+
+/// Check if object is an empty string, bytes or tuple, Null or Undefined
+fn is_empty(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let value = get_attr_direct(data, "value".to_string())?;
+
+        value.weak().with_data(|data| {
+            let empty = match data {
+                OnionObject::String(s) => s.is_empty(),
+                OnionObject::Bytes(b) => b.is_empty(),
+                OnionObject::Tuple(t) => t.get_elements().is_empty(),
+                OnionObject::Null | OnionObject::Undefined(_) => true,
+                _ => false,
+            };
+            Ok(OnionObject::Boolean(empty).stabilize())
+        })
+    })
+}
+
+/// Collect the entries of a dict-shaped tuple of Named values
+fn named_entries(
+    obj: &OnionObject,
+    func_name: &str,
+) -> Result<IndexMap<String, OnionStaticObject>, RuntimeError> {
+    match obj {
+        OnionObject::Tuple(tuple) => {
+            let mut entries = IndexMap::new();
+            for element in tuple.get_elements() {
+                if !matches!(element, OnionObject::Named(_)) {
+                    return Err(RuntimeError::InvalidOperation(
+                        format!("{} requires tuples of named entries", func_name).into(),
+                    ));
+                }
+                let key = element.key_of()?.weak().to_string(&vec![])?;
+                entries.insert(key, element.value_of()?);
+            }
+            Ok(entries)
+        }
+        _ => Err(RuntimeError::InvalidOperation(
+            format!("{} requires tuples of named entries", func_name).into(),
+        )),
+    }
+}
+
+/// Merge a dict over a dict of defaults, keeping the defaults' key order
+fn with_defaults(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let value = get_attr_direct(data, "value".to_string())?;
+        let defaults = get_attr_direct(data, "defaults".to_string())?;
+        let value = value
+            .weak()
+            .with_data(|value_data| named_entries(value_data, "with_defaults"))?;
+        let mut merged = defaults
+            .weak()
+            .with_data(|defaults_data| named_entries(defaults_data, "with_defaults"))?;
+        for (key, entry) in value {
+            merged.insert(key, entry);
+        }
+        Ok(build_named_dict(merged))
+    })
+}
+
+/// Return the argument unchanged
+fn identity(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument
+        .weak()
+        .with_data(|data| get_attr_direct(data, "value".to_string()))
+}
+
+/// Lambda body that ignores its input and returns a fixed value
+#[derive(Clone)]
+pub struct Const {
+    value: OnionStaticObject,
+}
+
+impl Runnable for Const {
+    fn step(&mut self, _gc: &mut GC<OnionObjectCell>) -> StepResult {
+        StepResult::Return(self.value.clone().into())
+    }
+
+    fn receive(
+        &mut self,
+        step_result: &StepResult,
+        _gc: &mut GC<OnionObjectCell>,
+    ) -> Result<(), RuntimeError> {
+        match step_result {
+            StepResult::Return(_) | StepResult::SetSelfObject(_) => Ok(()),
+            _ => Err(RuntimeError::DetailedError(
+                "Const received unexpected step result".to_string().into(),
+            )),
+        }
+    }
+
+    fn copy(&self) -> Box<dyn Runnable> {
+        Box::new(self.clone())
+    }
+
+    fn format_context(&self) -> Result<serde_json::Value, RuntimeError> {
+        Ok(serde_json::json!({
+            "type": "Const",
+            "value": self.value.to_string(),
+        }))
+    }
+}
+
+/// Build a lambda that always returns the given value
+fn constant(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let value = get_attr_direct(data, "value".to_string())?;
+        let mut params = IndexMap::new();
+        params.insert("input".to_string(), OnionObject::Null.stabilize());
+        Ok(OnionLambdaDefinition::new_static(
+            &build_named_dict(params),
+            LambdaBody::NativeFunction(Box::new(Const { value })),
+            None,
+            None,
+            "types::const".to_string(),
+        ))
+    })
+}
+
+/// Follow a path of keys and indices into nested dicts and tuples, or return default
+fn deep_get(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let obj = get_attr_direct(data, "obj".to_string())?;
+        let path = get_attr_direct(data, "path".to_string())?;
+        let default = get_attr_direct(data, "default".to_string())?;
+
+        let keys = match path.weak() {
+            OnionObject::Tuple(tuple) => tuple.get_elements().clone(),
+            _ => {
+                return Err(RuntimeError::InvalidOperation(
+                    "deep_get requires a tuple path".to_string().into(),
+                ))
+            }
+        };
+
+        let mut current = obj;
+        for key in keys.iter() {
+            match lookup(&current, key)? {
+                Some(next) => current = next,
+                None => return Ok(default),
+            }
+        }
+        Ok(current)
+    })
+}
+
+/// Index a tuple by integer or look up a key, returning None when it is missing
+fn lookup(
+    current: &OnionStaticObject,
+    key: &OnionObject,
+) -> Result<Option<OnionStaticObject>, RuntimeError> {
+    current
+        .weak()
+        .with_data(|current_data| match (current_data, key) {
+            (OnionObject::Tuple(tuple), OnionObject::Integer(index)) => Ok(usize::try_from(*index)
+                .ok()
+                .and_then(|index| tuple.get_elements().get(index))
+                .map(|element| element.stabilize())),
+            _ => match current_data.with_attribute(key, &|obj| Ok(obj.stabilize())) {
+                Ok(value) => Ok(Some(value)),
+                Err(RuntimeError::InvalidOperation(_)) => Ok(None),
+                Err(e) => Err(e),
+            },
+        })
+}
+
+/// Split a path like `a.b[0].c` into string keys and integer indices
+fn parse_json_path(path: &str) -> Result<Vec<OnionObject>, RuntimeError> {
+    let invalid =
+        || RuntimeError::InvalidOperation(format!("json_path cannot parse path '{}'", path).into());
+    let mut keys = vec![];
+    if path.is_empty() {
+        return Ok(keys);
+    }
+    for (i, segment) in path.split('.').enumerate() {
+        let (name, mut rest) = match segment.find('[') {
+            Some(pos) => segment.split_at(pos),
+            None => (segment, ""),
+        };
+        if name.is_empty() && (i > 0 || rest.is_empty()) {
+            return Err(invalid());
+        }
+        if !name.is_empty() {
+            keys.push(OnionObject::String(name.to_string().into()));
+        }
+        while !rest.is_empty() {
+            let close = rest.find(']').ok_or_else(invalid)?;
+            let index = rest[1..close]
+                .trim()
+                .parse::<i64>()
+                .map_err(|_| invalid())?;
+            keys.push(OnionObject::Integer(index));
+            rest = &rest[close + 1..];
+            if !rest.is_empty() && !rest.starts_with('[') {
+                return Err(invalid());
+            }
+        }
+    }
+    Ok(keys)
+}
+
+/// Follow a dotted and bracketed path string into nested dicts and tuples, or return Null
+fn json_path(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let obj = get_attr_direct(data, "obj".to_string())?;
+        let path = get_attr_direct(data, "path".to_string())?;
+
+        let keys = match path.weak() {
+            OnionObject::String(path) => parse_json_path(path)?,
+            _ => {
+                return Err(RuntimeError::InvalidOperation(
+                    "json_path requires a string path".to_string().into(),
+                ))
+            }
+        };
+
+        let mut current = obj;
+        for key in keys.iter() {
+            match lookup(&current, key)? {
+                Some(next) => current = next,
+                None => return Ok(OnionObject::Null.stabilize()),
+            }
+        }
+        Ok(current)
+    })
+}
+
+/// Thread a value through a tuple of lambdas from left to right
+#[derive(Clone)]
+pub struct Pipe {
+    funcs: Option<Vec<OnionStaticObject>>,
+    index: usize,
+    value: OnionStaticObject,
+}
+
+impl Default for Pipe {
+    fn default() -> Self {
+        Pipe {
+            funcs: None,
+            index: 0,
+            value: onion_tuple!(),
+        }
+    }
+}
+
+impl LambdaCalling for Pipe {
+    const NAME: &'static str = "Pipe";
+
+    fn resume(
+        &mut self,
+        argument: &OnionStaticObject,
+        result: Option<OnionStaticObject>,
+    ) -> Result<CallStep, RuntimeError> {
+        if let Some(result) = result {
+            self.value = result;
+        }
+        if self.funcs.is_none() {
+            let (value, funcs) = argument.weak().with_data(|data| {
+                let value = get_attr_direct(data, "value".to_string())?;
+                let funcs = get_attr_direct(data, "funcs".to_string())?;
+                Ok((value, funcs))
+            })?;
+            let funcs = funcs.weak().with_data(|funcs| match funcs {
+                OnionObject::Tuple(tuple) => Ok(tuple
+                    .get_elements()
+                    .iter()
+                    .map(|func| func.stabilize())
+                    .collect()),
+                _ => Err(RuntimeError::InvalidOperation(
+                    "pipe requires a tuple of functions".to_string().into(),
+                )),
+            })?;
+            self.value = value;
+            self.funcs = Some(funcs);
+        }
+
+        match self.funcs.as_ref().and_then(|funcs| funcs.get(self.index)) {
+            Some(func) => {
+                self.index += 1;
+                Ok(CallStep::Call(func.clone(), vec![self.value.clone()]))
+            }
+            None => Ok(CallStep::Return(self.value.clone())),
+        }
+    }
+
+    fn context(&self) -> serde_json::Map<String, serde_json::Value> {
+        let mut context = serde_json::Map::new();
+        context.insert("index".to_string(), self.index.into());
+        context
+    }
+}
+
+/// Return the result of the first case whose match value equals value, else default
+fn switch(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let value = get_attr_direct(data, "value".to_string())?;
+        let cases = get_attr_direct(data, "cases".to_string())?;
+        let default = get_attr_direct(data, "default".to_string())?;
+
+        cases.weak().with_data(|cases_data| match cases_data {
+            OnionObject::Tuple(cases) => {
+                for case in cases.get_elements() {
+                    let (candidate, result) = match case {
+                        OnionObject::Pair(_) => (case.key_of()?, case.value_of()?),
+                        OnionObject::Tuple(pair) if pair.get_elements().len() == 2 => (
+                            pair.get_elements()[0].stabilize(),
+                            pair.get_elements()[1].stabilize(),
+                        ),
+                        _ => {
+                            return Err(RuntimeError::InvalidOperation(
+                                "switch cases must be pairs of (match_value, result)"
+                                    .to_string()
+                                    .into(),
+                            ))
+                        }
+                    };
+                    if value.weak().equals(candidate.weak())? {
+                        return Ok(result);
+                    }
+                }
+                Ok(default.clone())
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "switch requires a tuple of cases".to_string().into(),
+            )),
+        })
+    })
+}
+
+/// Lambda body that calls `func` with pre-bound arguments merged under the incoming ones
+#[derive(Clone)]
+pub struct Partial {
+    func: OnionStaticObject,
+    bound: IndexMap<String, OnionStaticObject>,
+}
+
+impl LambdaCalling for Partial {
+    const NAME: &'static str = "Partial";
+
+    fn resume(
+        &mut self,
+        argument: &OnionStaticObject,
+        result: Option<OnionStaticObject>,
+    ) -> Result<CallStep, RuntimeError> {
+        if let Some(result) = result {
+            return Ok(CallStep::Return(result));
+        }
+
+        let args = argument
+            .weak()
+            .with_data(|data| get_attr_direct(data, "args".to_string()))?;
+        let incoming = args.weak().with_data(|args| match args {
+            OnionObject::Named(_) => {
+                let mut entries = IndexMap::new();
+                entries.insert(args.key_of()?.weak().to_string(&vec![])?, args.value_of()?);
+                Ok(entries)
+            }
+            OnionObject::Tuple(tuple)
+                if tuple
+                    .get_elements()
+                    .iter()
+                    .all(|element| matches!(element, OnionObject::Named(_))) =>
+            {
+                named_entries(args, "partial")
+            }
+            _ => Err(RuntimeError::InvalidOperation(
+                "partial lambdas only accept named arguments, e.g. f(b => 1)"
+                    .to_string()
+                    .into(),
+            )),
+        })?;
+        let mut merged = self.bound.clone();
+        for (key, value) in incoming {
+            merged.insert(key, value);
+        }
+        let named = merged
+            .into_iter()
+            .map(|(key, value)| {
+                OnionNamed::new_static(&OnionObject::String(key.into()).stabilize(), &value)
+            })
+            .collect();
+        Ok(CallStep::Call(self.func.clone(), named))
+    }
+
+    fn context(&self) -> serde_json::Map<String, serde_json::Value> {
+        let mut context = serde_json::Map::new();
+        context.insert(
+            "bound".to_string(),
+            self.bound.keys().cloned().collect::<Vec<_>>().into(),
+        );
+        context
+    }
+}
+
+/// Build a lambda that calls func with bound_args merged under its own arguments
+fn partial(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let func = get_attr_direct(data, "func".to_string())?;
+        let bound_args = get_attr_direct(data, "bound_args".to_string())?;
+        let bound = bound_args
+            .weak()
+            .with_data(|bound_data| named_entries(bound_data, "partial"))?;
+        let mut params = IndexMap::new();
+        params.insert("args".to_string(), onion_tuple!());
+        Ok(OnionLambdaDefinition::new_static(
+            &build_named_dict(params),
+            LambdaBody::NativeFunction(Box::new(LambdaCaller::new(Partial { func, bound }))),
+            None,
+            None,
+            "types::partial".to_string(),
+        ))
+    })
+}
+
+/// Lambda body for a curried two-argument function.
+///
+/// Without a captured first argument it returns a lambda capturing `a`; with one
+/// it calls `func` with the captured value and `b`.
+#[derive(Clone)]
+pub struct Curry2 {
+    func: OnionStaticObject,
+    first: Option<OnionStaticObject>,
+}
+
+impl Curry2 {
+    fn definition(self, param: &str, description: &str) -> OnionStaticObject {
+        let mut params = IndexMap::new();
+        params.insert(
+            param.to_string(),
+            OnionObject::Undefined(Some(description.to_string().into())).stabilize(),
+        );
+        OnionLambdaDefinition::new_static(
+            &build_named_dict(params),
+            LambdaBody::NativeFunction(Box::new(LambdaCaller::new(self))),
+            None,
+            None,
+            "types::curried".to_string(),
+        )
+    }
+}
+
+impl LambdaCalling for Curry2 {
+    const NAME: &'static str = "Curry2";
+
+    fn resume(
+        &mut self,
+        argument: &OnionStaticObject,
+        result: Option<OnionStaticObject>,
+    ) -> Result<CallStep, RuntimeError> {
+        if let Some(result) = result {
+            return Ok(CallStep::Return(result));
+        }
+
+        match self.first.clone() {
+            None => {
+                let a = argument
+                    .weak()
+                    .with_data(|data| get_attr_direct(data, "a".to_string()))?;
+                let inner = Curry2 {
+                    func: self.func.clone(),
+                    first: Some(a),
+                };
+                Ok(CallStep::Return(
+                    inner.definition("b", "Second argument to the function"),
+                ))
+            }
+            Some(first) => {
+                let b = argument
+                    .weak()
+                    .with_data(|data| get_attr_direct(data, "b".to_string()))?;
+                Ok(CallStep::Call(self.func.clone(), vec![first, b]))
+            }
+        }
+    }
+
+    fn context(&self) -> serde_json::Map<String, serde_json::Value> {
+        let mut context = serde_json::Map::new();
+        context.insert(
+            "first".to_string(),
+            self.first.as_ref().map(|first| first.to_string()).into(),
+        );
+        context
+    }
+}
+
+/// Turn a two-argument lambda into nested one-argument lambdas
+fn curry2(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let func = get_attr_direct(data, "func".to_string())?;
+        Ok(Curry2 { func, first: None }.definition("a", "First argument to the function"))
+    })
+}
+
+/// Call a lambda with the elements of a tuple as its arguments
+#[derive(Clone, Default)]
+pub struct Apply;
+
+impl LambdaCalling for Apply {
+    const NAME: &'static str = "Apply";
+
+    fn resume(
+        &mut self,
+        argument: &OnionStaticObject,
+        result: Option<OnionStaticObject>,
+    ) -> Result<CallStep, RuntimeError> {
+        if let Some(result) = result {
+            return Ok(CallStep::Return(result));
+        }
+
+        let (func, args) = argument.weak().with_data(|data| {
+            let func = get_attr_direct(data, "func".to_string())?;
+            let args = get_attr_direct(data, "args_tuple".to_string())?;
+            Ok((func, args))
+        })?;
+        let args = args.weak().with_data(|args| match args {
+            OnionObject::Tuple(tuple) => Ok(tuple
+                .get_elements()
+                .iter()
+                .map(|arg| arg.stabilize())
+                .collect()),
+            _ => Err(RuntimeError::InvalidOperation(
+                "apply requires a tuple of arguments".to_string().into(),
+            )),
+        })?;
+        Ok(CallStep::Call(func, args))
+    }
+}
+
+/// Return value if it has the given type, coercing Integer to Float and Boolean to Integer
+fn ensure(
+    argument: &OnionStaticObject,
+    _gc: &mut GC<OnionObjectCell>,
+) -> Result<OnionStaticObject, RuntimeError> {
+    argument.weak().with_data(|data| {
+        let value = get_attr_direct(data, "value".to_string())?;
+        let type_name = get_attr_direct(data, "type_name".to_string())?;
+        let type_name = type_name.weak().to_string(&vec![])?;
+
+        value.weak().with_data(|value_data| {
+            let actual = value_data.type_of()?;
+            if actual == type_name {
+                return Ok(value.clone());
+            }
+            let float_name = OnionObject::Float(0.0).type_of()?;
+            let integer_name = OnionObject::Integer(0).type_of()?;
+            let coerced = match value_data {
+                // only integers a double represents exactly
+                OnionObject::Integer(n)
+                    if type_name == float_name && n.unsigned_abs() <= 1 << 53 =>
+                {
+                    Some(OnionObject::Float(*n as f64))
+                }
+                OnionObject::Boolean(b) if type_name == integer_name => {
+                    Some(OnionObject::Integer(*b as i64))
+                }
+                _ => None,
+            };
+            coerced.map(|coerced| coerced.stabilize()).ok_or_else(|| {
+                RuntimeError::InvalidType(
+                    format!("ensure expected {}, got {}", type_name, actual).into(),
+                )
+            })
+        })
+    })
+}
+
 /// Build the type conversion module
 pub fn build_module() -> OnionStaticObject {
     let mut module = IndexMap::new();
@@ -457,6 +1314,365 @@ pub fn build_module() -> OnionStaticObject {
         ),
     );
 
+    // validate 函数
+    let mut validate_params = IndexMap::new();
+    validate_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some("Value to validate".to_string().into())).stabilize(),
+    );
+    validate_params.insert(
+        "schema".to_string(),
+        OnionObject::Undefined(Some(
+            "Schema mapping field names to type names"
+                .to_string()
+                .into(),
+        ))
+        .stabilize(),
+    );
+    module.insert(
+        "validate".to_string(),
+        wrap_native_function(
+            &build_named_dict(validate_params),
+            None,
+            None,
+            "types::validate".to_string(),
+            &validate,
+        ),
+    );
+
+    // enum_from 函数
+    let mut enum_from_params = IndexMap::new();
+    enum_from_params.insert(
+        "names".to_string(),
+        OnionObject::Undefined(Some("Tuple of enum member names".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "enum_from".to_string(),
+        wrap_native_function(
+            &build_named_dict(enum_from_params),
+            None,
+            None,
+            "types::enum_from".to_string(),
+            &enum_from,
+        ),
+    );
+
+    // memoize 函数
+    let mut memoize_params = IndexMap::new();
+    memoize_params.insert(
+        "func".to_string(),
+        OnionObject::Undefined(Some("Function whose results are cached".to_string().into()))
+            .stabilize(),
+    );
+    module.insert(
+        "memoize".to_string(),
+        wrap_native_function(
+            &build_named_dict(memoize_params),
+            None,
+            None,
+            "types::memoize".to_string(),
+            &memoize,
+        ),
+    );
+
+    // pretty 函数
+    let mut pretty_params = IndexMap::new();
+    pretty_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some("Value to render".to_string().into())).stabilize(),
+    );
+    pretty_params.insert("indent".to_string(), OnionObject::Integer(2).stabilize());
+    module.insert(
+        "pretty".to_string(),
+        wrap_native_function(
+            &build_named_dict(pretty_params),
+            None,
+            None,
+            "types::pretty".to_string(),
+            &pretty,
+        ),
+    );
+
+    // is_empty 函数
+    let mut is_empty_params = IndexMap::new();
+    is_empty_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some("Value to check".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "is_empty".to_string(),
+        wrap_native_function(
+            &build_named_dict(is_empty_params),
+            None,
+            None,
+            "types::is_empty".to_string(),
+            &is_empty,
+        ),
+    );
+
+    // with_defaults 函数
+    let mut with_defaults_params = IndexMap::new();
+    with_defaults_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some(
+            "Dict of values that take precedence".to_string().into(),
+        ))
+        .stabilize(),
+    );
+    with_defaults_params.insert(
+        "defaults".to_string(),
+        OnionObject::Undefined(Some("Dict of default values".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "with_defaults".to_string(),
+        wrap_native_function(
+            &build_named_dict(with_defaults_params),
+            None,
+            None,
+            "types::with_defaults".to_string(),
+            &with_defaults,
+        ),
+    );
+
+    // identity 函数
+    let mut identity_params = IndexMap::new();
+    identity_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some("Value to return".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "identity".to_string(),
+        wrap_native_function(
+            &build_named_dict(identity_params),
+            None,
+            None,
+            "types::identity".to_string(),
+            &identity,
+        ),
+    );
+
+    // const 函数
+    let mut const_params = IndexMap::new();
+    const_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some(
+            "Value the returned lambda always produces"
+                .to_string()
+                .into(),
+        ))
+        .stabilize(),
+    );
+    module.insert(
+        "const".to_string(),
+        wrap_native_function(
+            &build_named_dict(const_params),
+            None,
+            None,
+            "types::const".to_string(),
+            &constant,
+        ),
+    );
+
+    // deep_get 函数
+    let mut deep_get_params = IndexMap::new();
+    deep_get_params.insert(
+        "obj".to_string(),
+        OnionObject::Undefined(Some("Nested dict or tuple to read from".to_string().into()))
+            .stabilize(),
+    );
+    deep_get_params.insert(
+        "path".to_string(),
+        OnionObject::Undefined(Some(
+            "Tuple of keys and indices to follow".to_string().into(),
+        ))
+        .stabilize(),
+    );
+    deep_get_params.insert(
+        "default".to_string(),
+        OnionObject::Undefined(None).stabilize(),
+    );
+    module.insert(
+        "deep_get".to_string(),
+        wrap_native_function(
+            &build_named_dict(deep_get_params),
+            None,
+            None,
+            "types::deep_get".to_string(),
+            &deep_get,
+        ),
+    );
+
+    // pipe 函数
+    let mut pipe_params = IndexMap::new();
+    pipe_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some("Initial value".to_string().into())).stabilize(),
+    );
+    pipe_params.insert(
+        "funcs".to_string(),
+        OnionObject::Undefined(Some(
+            "Tuple of functions applied in order".to_string().into(),
+        ))
+        .stabilize(),
+    );
+    module.insert(
+        "pipe".to_string(),
+        OnionLambdaDefinition::new_static(
+            &build_named_dict(pipe_params),
+            LambdaBody::NativeFunction(Box::new(LambdaCaller::new(Pipe::default()))),
+            None,
+            None,
+            "types::pipe".to_string(),
+        ),
+    );
+
+    // switch 函数
+    let mut switch_params = IndexMap::new();
+    switch_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some("Value to match".to_string().into())).stabilize(),
+    );
+    switch_params.insert(
+        "cases".to_string(),
+        OnionObject::Undefined(Some(
+            "Tuple of (match_value, result) pairs".to_string().into(),
+        ))
+        .stabilize(),
+    );
+    switch_params.insert(
+        "default".to_string(),
+        OnionObject::Undefined(None).stabilize(),
+    );
+    module.insert(
+        "switch".to_string(),
+        wrap_native_function(
+            &build_named_dict(switch_params),
+            None,
+            None,
+            "types::switch".to_string(),
+            &switch,
+        ),
+    );
+
+    // partial 函数
+    let mut partial_params = IndexMap::new();
+    partial_params.insert(
+        "func".to_string(),
+        OnionObject::Undefined(Some(
+            "Function to call; the returned lambda only accepts named arguments"
+                .to_string()
+                .into(),
+        ))
+        .stabilize(),
+    );
+    partial_params.insert(
+        "bound_args".to_string(),
+        OnionObject::Undefined(Some(
+            "Dict of arguments bound ahead of the call"
+                .to_string()
+                .into(),
+        ))
+        .stabilize(),
+    );
+    module.insert(
+        "partial".to_string(),
+        wrap_native_function(
+            &build_named_dict(partial_params),
+            None,
+            None,
+            "types::partial".to_string(),
+            &partial,
+        ),
+    );
+
+    // curry2 函数
+    let mut curry2_params = IndexMap::new();
+    curry2_params.insert(
+        "func".to_string(),
+        OnionObject::Undefined(Some("Two-argument function to curry".to_string().into()))
+            .stabilize(),
+    );
+    module.insert(
+        "curry2".to_string(),
+        wrap_native_function(
+            &build_named_dict(curry2_params),
+            None,
+            None,
+            "types::curry2".to_string(),
+            &curry2,
+        ),
+    );
+
+    // apply 函数
+    let mut apply_params = IndexMap::new();
+    apply_params.insert(
+        "func".to_string(),
+        OnionObject::Undefined(Some("Function to call".to_string().into())).stabilize(),
+    );
+    apply_params.insert(
+        "args_tuple".to_string(),
+        OnionObject::Undefined(Some(
+            "Tuple of arguments passed to the function"
+                .to_string()
+                .into(),
+        ))
+        .stabilize(),
+    );
+    module.insert(
+        "apply".to_string(),
+        OnionLambdaDefinition::new_static(
+            &build_named_dict(apply_params),
+            LambdaBody::NativeFunction(Box::new(LambdaCaller::new(Apply))),
+            None,
+            None,
+            "types::apply".to_string(),
+        ),
+    );
+
+    // ensure 函数
+    let mut ensure_params = IndexMap::new();
+    ensure_params.insert(
+        "value".to_string(),
+        OnionObject::Undefined(Some("Value to check".to_string().into())).stabilize(),
+    );
+    ensure_params.insert(
+        "type_name".to_string(),
+        OnionObject::Undefined(Some("Type name the value must have".to_string().into()))
+            .stabilize(),
+    );
+    module.insert(
+        "ensure".to_string(),
+        wrap_native_function(
+            &build_named_dict(ensure_params),
+            None,
+            None,
+            "types::ensure".to_string(),
+            &ensure,
+        ),
+    );
+
+    // json_path 函数
+    let mut json_path_params = IndexMap::new();
+    json_path_params.insert(
+        "obj".to_string(),
+        OnionObject::Undefined(Some("Nested dict or tuple to read from".to_string().into()))
+            .stabilize(),
+    );
+    json_path_params.insert(
+        "path".to_string(),
+        OnionObject::Undefined(Some("Path string such as a.b[0].c".to_string().into())).stabilize(),
+    );
+    module.insert(
+        "json_path".to_string(),
+        wrap_native_function(
+            &build_named_dict(json_path_params),
+            None,
+            None,
+            "types::json_path".to_string(),
+            &json_path,
+        ),
+    );
+
     module.insert("tuple".to_string(), tuple::build_module());
 
     build_named_dict(module)
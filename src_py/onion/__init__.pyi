@@ -1,4 +1,4 @@
-from typing import Any, Optional, Callable, Awaitable, List, overload
+from typing import Any, Optional, Callable, Awaitable, Dict, List, Union, overload
 
 __version__: str
 
@@ -96,15 +96,50 @@ class PyOnionObject:
         """Convert the object to a named object."""
         ...
 
+    def as_dict(self) -> Dict[Any, Any]:
+        """
+        Convert a tuple of named entries to a Python dict, converting values recursively.
+
+        :raises TypeError: If the object is not a tuple of named entries
+        """
+        ...
+
+    def pretty(self, indent: int = 2) -> str:
+        """Render the object as an indented multi-line tree for debugging."""
+        ...
+
+    def map_py(self, callback: Callable[["PyOnionObject"], Any]) -> "PyOnionObject":
+        """
+        Apply a Python callback to every element of a tuple.
+
+        :param callback: Called with each element as a PyOnionObject; its result is converted back
+        :return: New tuple of the converted results
+        :raises TypeError: If the object is not a tuple
+        """
+        ...
+
     def unwrap_py(self) -> Any:
         """Unwrap the Python object from custom types."""
         ...
 
+    def to_py(self) -> Any:
+        """
+        Recursively convert the object into native Python values.
+
+        Tuples become lists, pairs become 2-tuples and named values become
+        single-entry dicts. Wrapped Python objects are returned as-is.
+        """
+        ...
+
     # --- Other operations ---
     def type_name(self) -> str:
         """Get the Onion type name of the object."""
         ...
 
+    def type_matches(self, name: str) -> bool:
+        """Return True if the Onion type name of the object equals `name`."""
+        ...
+
     def __repr__(self) -> str:
         """Return the string representation of the object."""
         ...
@@ -129,6 +164,19 @@ class PyOnionObject:
         """Return the Pythonic length of the object."""
         ...
 
+    def __call__(self, *args: Any, **kwargs: Any) -> "PyOnionObject":
+        """
+        Call an Onion lambda synchronously.
+
+        Positional arguments become tuple elements and keyword arguments become
+        named entries. Raises OnionPending if the lambda awaits a Python coroutine.
+        """
+        ...
+
+    def __iter__(self) -> "PyOnionObjectIterator":
+        """Iterate over the elements of a tuple or range; raises TypeError otherwise."""
+        ...
+
     def __contains__(self, item: Any) -> bool:
         """Perform a membership test."""
         ...
@@ -177,21 +225,90 @@ class PyOnionObject:
         """Create a new tuple object from a list of elements."""
         ...
 
+class PyOnionObjectIterator:
+    """Iterator over the elements of a PyOnionObject tuple or range."""
+
+    def __iter__(self) -> "PyOnionObjectIterator":
+        """Return the iterator itself."""
+        ...
+
+    def __next__(self) -> PyOnionObject:
+        """Return the next element or raise StopIteration."""
+        ...
+
 async def eval(
     code: str,
     work_dir: Optional[str] = ...,
-    context: Optional[List[PyOnionObject]] = ...,
+    context: Optional[Union[List[PyOnionObject], PyOnionObject]] = ...,
+    timeout_ms: Optional[int] = ...,
+    max_steps: Optional[int] = ...,
+    extra_stdlib: Optional[Dict[str, Any]] = ...,
 ) -> PyOnionObject:
     """
     Evaluate Onion script asynchronously.
 
     :param code: Onion script code
     :param work_dir: Optional working directory
-    :param context: Optional context variables, as a list of OnionNamed objects
+    :param context: Optional context variables, as a list or tuple of OnionNamed objects
+    :param timeout_ms: Optional wall-clock budget in milliseconds
+    :param max_steps: Optional limit on scheduler steps; None means unlimited
+    :param extra_stdlib: Optional mapping of names to values exposed under ``stdlib``
     :return: Result as PyOnionObject
+    :raises OnionTimeout: If the script runs longer than timeout_ms
+    :raises OnionStepLimitExceeded: If the script takes more than max_steps steps
     """
     ...
 
+def eval_sync(
+    code: str,
+    work_dir: Optional[str] = ...,
+    context: Optional[Union[List[PyOnionObject], PyOnionObject]] = ...,
+    timeout_ms: Optional[int] = ...,
+    max_steps: Optional[int] = ...,
+    extra_stdlib: Optional[Dict[str, Any]] = ...,
+) -> PyOnionObject:
+    """
+    Evaluate Onion script synchronously on the calling thread.
+
+    :param code: Onion script code
+    :param work_dir: Optional working directory
+    :param context: Optional context variables, as a list or tuple of OnionNamed objects
+    :param timeout_ms: Optional wall-clock budget in milliseconds
+    :param max_steps: Optional limit on scheduler steps; None means unlimited
+    :param extra_stdlib: Optional mapping of names to values exposed under ``stdlib``
+    :return: Result as PyOnionObject
+    :raises RuntimeError: If the script fails to compile or awaits a Python coroutine
+    :raises OnionTimeout: If the script runs longer than timeout_ms
+    :raises OnionStepLimitExceeded: If the script takes more than max_steps steps
+    """
+    ...
+
+def compile(code: str, work_dir: Optional[str] = ...) -> "PyOnionProgram":
+    """
+    Compile Onion script once for repeated execution.
+
+    :param code: Onion script code
+    :param work_dir: Optional working directory used to resolve imports
+    :return: Compiled program
+    :raises RuntimeError: If the script fails to compile
+    """
+    ...
+
+class PyOnionProgram:
+    """A compiled Onion script that can be run many times."""
+
+    async def run(
+        self,
+        context: Optional[Union[List[PyOnionObject], PyOnionObject]] = ...,
+    ) -> PyOnionObject:
+        """
+        Execute the compiled script asynchronously.
+
+        :param context: Optional context variables, as a list or tuple of OnionNamed objects
+        :return: Result as PyOnionObject
+        """
+        ...
+
 def wrap_py_function(
     params: Any,
     signature: str,
@@ -230,6 +347,41 @@ def wrap_py_coroutine(
     """
     ...
 
+def import_py(module_name: str, names: List[str]) -> PyOnionObject:
+    """
+    Import callables from a Python module for use inside Onion scripts.
+
+    :param module_name: Name of the Python module to import
+    :param names: Names of the callables to expose
+    :return: Named tuple mapping each name to a wrapped callable, usable as `eval` context
+    """
+    ...
+
+class OnionError(Exception):
+    """
+    Base class for errors raised by the Onion runtime that have no closer Python equivalent.
+    Type mismatches raise TypeError and invalid operations raise ValueError instead.
+    """
+    ...
+
+class OnionPending(OnionError):
+    """
+    Raised when an Onion operation is still pending.
+    """
+    ...
+
+class OnionTimeout(OnionError):
+    """
+    Raised when a script runs longer than its timeout_ms budget.
+    """
+    ...
+
+class OnionStepLimitExceeded(OnionError):
+    """
+    Raised when a script takes more scheduler steps than its max_steps budget.
+    """
+    ...
+
 class OnionRuntimeError(RuntimeError):
     """
     OnionRuntimeError is raised when an Onion script evaluation fails.
@@ -240,15 +392,23 @@ class OnionRuntimeError(RuntimeError):
 async def eval_or_throw(
     code: str,
     work_dir: Optional[str] = ...,
-    context: Optional[List[PyOnionObject]] = ...,
+    context: Optional[Union[List[PyOnionObject], PyOnionObject]] = ...,
+    timeout_ms: Optional[int] = ...,
+    max_steps: Optional[int] = ...,
+    extra_stdlib: Optional[Dict[str, Any]] = ...,
 ) -> PyOnionObject:
     """
     Evaluate Onion script asynchronously.
 
     :param code: Onion script code
     :param work_dir: Optional working directory
-    :param context: Optional context variables, as a list of OnionNamed objects
+    :param context: Optional context variables, as a list or tuple of OnionNamed objects
+    :param timeout_ms: Optional wall-clock budget in milliseconds
+    :param max_steps: Optional limit on scheduler steps; None means unlimited
+    :param extra_stdlib: Optional mapping of names to values exposed under ``stdlib``
     :return: Result as PyOnionObject
     :raises OnionRuntimeError: If the evaluation fails, it raises OnionRuntimeError with the error details.
+    :raises OnionTimeout: If the script runs longer than timeout_ms.
+    :raises OnionStepLimitExceeded: If the script takes more than max_steps steps.
     """
     ...
\ No newline at end of file
@@ -1,8 +1,21 @@
 """Onion Python 高级封装，导出核心 API。"""
 
 from __future__ import annotations
-from typing import Optional, List
-from onion.onion import eval, PyOnionObject, wrap_py_function, wrap_py_coroutine
+from typing import Any, Dict, Optional, List, Union
+from onion.onion import (
+    eval,
+    eval_sync,
+    compile,
+    PyOnionObject,
+    PyOnionProgram,
+    wrap_py_function,
+    wrap_py_coroutine,
+    import_py,
+    OnionError,
+    OnionPending,
+    OnionTimeout,
+    OnionStepLimitExceeded,
+)
 
 
 class OnionRuntimeError(RuntimeError):
@@ -18,18 +31,26 @@ class OnionRuntimeError(RuntimeError):
 async def eval_or_throw(
     code: str,
     work_dir: Optional[str] = None,
-    context: Optional[List[PyOnionObject]] = None,
+    context: Optional[Union[List[PyOnionObject], PyOnionObject]] = None,
+    timeout_ms: Optional[int] = None,
+    max_steps: Optional[int] = None,
+    extra_stdlib: Optional[Dict[str, Any]] = None,
 ) -> PyOnionObject:
     """
     Evaluate Onion script asynchronously.
 
     :param code: Onion script code
     :param work_dir: Optional working directory
-    :param context: Optional context variables, as a list of OnionNamed objects
+    :param context: Optional context variables, as a list or tuple of OnionNamed objects
+    :param timeout_ms: Optional wall-clock budget in milliseconds
+    :param max_steps: Optional limit on scheduler steps; None means unlimited
+    :param extra_stdlib: Optional mapping of names to values exposed under ``stdlib``
     :return: Result as PyOnionObject
     :raises OnionRuntimeError: If the evaluation fails, it raises OnionRuntimeError with the error details.
+    :raises OnionTimeout: If the script runs longer than timeout_ms.
+    :raises OnionStepLimitExceeded: If the script takes more than max_steps steps.
     """
-    result = await eval(code, work_dir, context)
+    result = await eval(code, work_dir, context, timeout_ms, max_steps, extra_stdlib)
     if not result.is_pair():
         raise RuntimeError(f"Cannot resolve result: {result}")
     k = result.key()
@@ -41,9 +62,17 @@ async def eval_or_throw(
 
 __all__ = [
     "eval",
+    "eval_sync",
+    "compile",
     "PyOnionObject",
+    "PyOnionProgram",
     "wrap_py_function",
     "wrap_py_coroutine",
+    "import_py",
+    "OnionError",
+    "OnionPending",
+    "OnionTimeout",
+    "OnionStepLimitExceeded",
     "OnionRuntimeError",
     "eval_or_throw",
 ]
@@ -1,15 +1,24 @@
 import unittest
 import asyncio
+import math
+import time
 from typing import Awaitable, Any
 import asyncio
 
 from onion import (
     eval,
+    eval_sync,
+    compile,
     PyOnionObject,
     wrap_py_function,
     wrap_py_coroutine,
+    import_py,
     eval_or_throw,
+    OnionError,
+    OnionPending,
     OnionRuntimeError,
+    OnionTimeout,
+    OnionStepLimitExceeded,
 )
 
 
@@ -60,6 +69,44 @@ class TestOnion(unittest.TestCase):
         )
         print("Dict:", dict_obj, "value1:", dict_obj.key1, "value2:", dict_obj.key2)
 
+    def test_type_matches(self):
+        obj = PyOnionObject(42)
+        self.assertTrue(obj.type_matches(obj.type_name()))
+        self.assertFalse(obj.type_matches(PyOnionObject("text").type_name()))
+
+    def test_as_dict(self):
+        obj = PyOnionObject(
+            [
+                PyOnionObject.named("name", "onion"),
+                PyOnionObject.named(
+                    "tags", PyOnionObject([PyOnionObject("a"), PyOnionObject("b")])
+                ),
+            ]
+        )
+        self.assertEqual(obj.as_dict(), {"name": "onion", "tags": ["a", "b"]})
+        nested = {"db": {"host": "localhost", "replicas": [{"port": 1}, {"port": 2}]}}
+        self.assertEqual(PyOnionObject(nested).as_dict(), nested)
+        with self.assertRaises(TypeError):
+            PyOnionObject([PyOnionObject(1), PyOnionObject(2)]).as_dict()
+
+    def test_pretty(self):
+        obj = PyOnionObject(
+            [
+                PyOnionObject.named(
+                    "outer", PyOnionObject([PyOnionObject(1), PyOnionObject(2)])
+                ),
+            ]
+        )
+        rendered = obj.pretty()
+        print(rendered)
+        self.assertIn('\n  "outer" => (\n    1,\n', rendered)
+
+    def test_map_py(self):
+        numbers = PyOnionObject([PyOnionObject(1), PyOnionObject(2), PyOnionObject(3)])
+        doubled = numbers.map_py(lambda element: element.as_integer() * 2)
+        self.assertTrue(doubled.is_tuple())
+        self.assertEqual([doubled[i].as_integer() for i in range(3)], [2, 4, 6])
+
     def test_call_py_function(self):
 
         def add(self_object: PyOnionObject, arguments: PyOnionObject):
@@ -137,6 +184,2243 @@ class TestOnion(unittest.TestCase):
 
         asyncio.run(test())
 
+    def test_import_py(self):
+        async def test():
+            context = import_py("math", ["sqrt"])
+            result = await eval_or_throw(
+                """
+                @required sqrt;
+                return sqrt(16);
+                """,
+                None,
+                context,
+            )
+            self.assertEqual(result.as_float(), 4.0)
+
+        asyncio.run(test())
+
+    def test_memoize(self):
+        calls = []
+
+        def expensive(self_object: PyOnionObject, arguments: PyOnionObject):
+            x = arguments.x.as_integer()
+            calls.append(x)
+            return x * x
+
+        async def test():
+            context = [
+                PyOnionObject.named(
+                    "expensive",
+                    wrap_py_function(
+                        PyOnionObject([PyOnionObject.named("x", None)]),
+                        "<python>::expensive",
+                        expensive,
+                        None,
+                        None,
+                    ),
+                ),
+            ]
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required expensive;
+                square := stdlib.types.memoize(expensive);
+                return (square(3), square(3), square(4), square(3));
+                """,
+                None,
+                context,
+            )
+            self.assertTrue(result.is_tuple())
+            self.assertEqual(calls, [3, 4])
+
+        asyncio.run(test())
+
+    def test_collections(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                stack := stdlib.collections.new_stack();
+                queue := stdlib.collections.new_queue();
+                stack.push(1); stack.push(2); stack.push(3);
+                queue.push(1); queue.push(2); queue.push(3);
+                return ((stack.pop(), stack.pop(), stack.pop()), (queue.pop(), queue.pop(), queue.pop()));
+                """,
+                None,
+                None,
+            )
+            stack_order = [result[0][i].as_integer() for i in range(3)]
+            queue_order = [result[1][i].as_integer() for i in range(3)]
+            self.assertEqual(stack_order, [3, 2, 1])
+            self.assertEqual(queue_order, [1, 2, 3])
+
+        asyncio.run(test())
+
+    def test_running_mean(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                mean := stdlib.math.running_mean();
+                mean.add(2);
+                first := mean.value();
+                mean.add(4);
+                second := mean.value();
+                mean.add(9);
+                return (first, second, mean.value(), mean.count());
+                """,
+                None,
+                None,
+            )
+            self.assertEqual(result[0].as_float(), 2.0)
+            self.assertEqual(result[1].as_float(), 3.0)
+            self.assertEqual(result[2].as_float(), 5.0)
+            self.assertEqual(result[3].as_integer(), 3)
+
+        asyncio.run(test())
+
+    def test_const(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                five := stdlib.types.const(5);
+                return (five(1), five("x"), stdlib.types.identity(7));
+                """,
+                None,
+                None,
+            )
+            self.assertEqual(result[0].as_integer(), 5)
+            self.assertEqual(result[1].as_integer(), 5)
+            self.assertEqual(result[2].as_integer(), 7)
+
+        asyncio.run(test())
+
+    def test_pipe(self):
+        def double(self_object: PyOnionObject, arguments: PyOnionObject):
+            return arguments.x.as_integer() * 2
+
+        def increment(self_object: PyOnionObject, arguments: PyOnionObject):
+            return arguments.x.as_integer() + 1
+
+        async def test():
+            params = PyOnionObject([PyOnionObject.named("x", None)])
+            context = [
+                PyOnionObject.named(
+                    "double",
+                    wrap_py_function(params, "<python>::double", double, None, None),
+                ),
+                PyOnionObject.named(
+                    "increment",
+                    wrap_py_function(
+                        params, "<python>::increment", increment, None, None
+                    ),
+                ),
+            ]
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required double;
+                @required increment;
+                return stdlib.types.pipe(5, (double, increment));
+                """,
+                None,
+                context,
+            )
+            self.assertEqual(result.as_integer(), 11)
+
+        asyncio.run(test())
+
+    def test_partial(self):
+        def subtract(self_object: PyOnionObject, arguments: PyOnionObject):
+            return arguments.a.as_integer() - arguments.b.as_integer()
+
+        async def test():
+            params = PyOnionObject(
+                [PyOnionObject.named("a", None), PyOnionObject.named("b", None)]
+            )
+            context = [
+                PyOnionObject.named(
+                    "subtract",
+                    wrap_py_function(
+                        params, "<python>::subtract", subtract, None, None
+                    ),
+                ),
+            ]
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required subtract;
+                from_ten := stdlib.types.partial(subtract, (a => 10,));
+                return (from_ten(b => 3), from_ten((a => 20, b => 3)));
+                """,
+                None,
+                context,
+            )
+            self.assertEqual(result[0].as_integer(), 7)
+            self.assertEqual(result[1].as_integer(), 17)
+
+        asyncio.run(test())
+
+    def test_curry2(self):
+        def add(self_object: PyOnionObject, arguments: PyOnionObject):
+            return arguments.a.as_integer() + arguments.b.as_integer()
+
+        async def test():
+            params = PyOnionObject(
+                [PyOnionObject.named("a", None), PyOnionObject.named("b", None)]
+            )
+            context = [
+                PyOnionObject.named(
+                    "add",
+                    wrap_py_function(params, "<python>::add", add, None, None),
+                ),
+            ]
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required add;
+                return stdlib.types.curry2(add)(2)(3);
+                """,
+                None,
+                context,
+            )
+            self.assertEqual(result.as_integer(), 5)
+
+        asyncio.run(test())
+
+    def test_error_types(self):
+        self.assertTrue(issubclass(OnionPending, OnionError))
+        with self.assertRaises(TypeError):
+            PyOnionObject(1).as_range()
+
+        async def test():
+            return await eval_or_throw(
+                """
+                @required stdlib;
+                return (stdlib.bigint.div, stdlib.time.sleep_millis);
+                """
+            )
+
+        functions = asyncio.run(test())
+        div, sleep_millis = functions[0], functions[1]
+
+        # InvalidOperation -> ValueError
+        with self.assertRaises(ValueError) as caught:
+            div(1, 0)
+        self.assertIn("Division by zero", caught.exception.args[0])
+
+        # DetailedError -> OnionError
+        with self.assertRaises(OnionError) as caught:
+            sleep_millis(-1)
+        self.assertNotIsInstance(caught.exception, (ValueError, TypeError))
+        self.assertIn("Sleep duration cannot be negative", caught.exception.args[0])
+
+    def test_apply(self):
+        def subtract(self_object: PyOnionObject, arguments: PyOnionObject):
+            return arguments.a.as_integer() - arguments.b.as_integer()
+
+        async def test():
+            params = PyOnionObject(
+                [PyOnionObject.named("a", None), PyOnionObject.named("b", None)]
+            )
+            context = [
+                PyOnionObject.named(
+                    "subtract",
+                    wrap_py_function(
+                        params, "<python>::subtract", subtract, None, None
+                    ),
+                ),
+            ]
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required subtract;
+                return stdlib.types.apply(subtract, (10, 4));
+                """,
+                None,
+                context,
+            )
+            self.assertEqual(result.as_integer(), 6)
+
+        asyncio.run(test())
+
+    def test_to_py(self):
+        marker = object()
+        obj = PyOnionObject(
+            [
+                PyOnionObject(1),
+                PyOnionObject(2.5),
+                PyOnionObject("onion"),
+                PyOnionObject(None),
+                PyOnionObject.pair(PyOnionObject("k"), PyOnionObject(True)),
+                PyOnionObject.named("n", PyOnionObject([PyOnionObject(3)])),
+                PyOnionObject(marker),
+            ]
+        )
+        self.assertEqual(
+            obj.to_py(),
+            [1, 2.5, "onion", None, ("k", True), {"n": [3]}, marker],
+        )
+
+    def test_dict_conversion(self):
+        config = {"name": "onion", "db": {"host": "localhost", "port": 5432}}
+
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required config;
+                return (config, config.db.port);
+                """,
+                None,
+                PyOnionObject({"config": config}),
+            )
+            self.assertEqual(result[0].as_dict(), config)
+            self.assertEqual(result[1].as_integer(), 5432)
+
+        asyncio.run(test())
+
+    def test_iter(self):
+        obj = PyOnionObject([PyOnionObject(1), PyOnionObject(2), PyOnionObject(3)])
+        self.assertEqual([element.as_integer() for element in obj], [1, 2, 3])
+        with self.assertRaises(TypeError):
+            iter(PyOnionObject(1))
+
+    def test_call(self):
+        async def test():
+            return await eval_or_throw(
+                """
+                return (x => 0, y => 0) -> x + y;
+                """,
+                None,
+                None,
+            )
+
+        add = asyncio.run(test())
+        self.assertEqual(add(2, 3).as_integer(), 5)
+        self.assertEqual(add(2, y=4).as_integer(), 6)
+
+    def test_ensure(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return stdlib.types.ensure(3, stdlib.types.type_of(1.5));
+                """,
+                None,
+                None,
+            )
+            self.assertEqual(result.as_float(), 3.0)
+            with self.assertRaises(OnionRuntimeError):
+                await eval_or_throw(
+                    """
+                    @required stdlib;
+                    return stdlib.types.ensure("3", stdlib.types.type_of(1));
+                    """,
+                    None,
+                    None,
+                )
+
+        asyncio.run(test())
+
+    def test_eval_sync(self):
+        result = eval_sync(
+            """
+            @required x;
+            return x * 2;
+            """,
+            None,
+            [PyOnionObject.named("x", 21)],
+        )
+        self.assertTrue(result.is_pair())
+        self.assertTrue(result.key().as_boolean())
+        self.assertEqual(result.value().as_integer(), 42)
+
+    def test_compile(self):
+        program = compile(
+            """
+            @required x;
+            return x + 1;
+            """
+        )
+
+        async def test():
+            results = []
+            for x in (1, 2):
+                result = await program.run([PyOnionObject.named("x", x)])
+                self.assertTrue(result.key().as_boolean())
+                results.append(result.value().as_integer())
+            return results
+
+        self.assertEqual(asyncio.run(test()), [2, 3])
+
+    def test_timeout(self):
+        code = """
+            i := 0;
+            while (true) {
+                i = i + 1;
+            };
+            """
+        with self.assertRaises(OnionTimeout):
+            eval_sync(code, None, None, 50)
+
+        async def test():
+            with self.assertRaises(OnionTimeout):
+                await eval(code, None, None, 50)
+
+        asyncio.run(test())
+
+    def test_tuple_min_max(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                values := (3, 1, 4, 1, 5);
+                negate := (x => 0) -> 0 - x;
+                return (
+                    stdlib.types.tuple.min(values),
+                    stdlib.types.tuple.max(values),
+                    stdlib.types.tuple.min_by(values, negate),
+                    stdlib.types.tuple.max_by(values, negate)
+                );
+                """,
+                None,
+                None,
+            )
+            self.assertEqual(result[0].as_integer(), 1)
+            self.assertEqual(result[1].as_integer(), 5)
+            self.assertEqual(result[2].as_integer(), 5)
+            self.assertEqual(result[3].as_integer(), 1)
+
+        asyncio.run(test())
+
+    def test_max_steps(self):
+        code = """
+            i := 0;
+            while (true) {
+                i = i + 1;
+            };
+            """
+        with self.assertRaises(OnionStepLimitExceeded):
+            eval_sync(code, max_steps=1000)
+
+        async def test():
+            with self.assertRaises(OnionStepLimitExceeded):
+                await eval_or_throw(code, max_steps=1000)
+            result = await eval_or_throw("1 + 2", max_steps=1000000)
+            self.assertEqual(result.as_integer(), 3)
+
+        asyncio.run(test())
+
+    def test_xor_cipher(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                plain := stdlib.bytes.from_string("attack at dawn");
+                key := stdlib.bytes.from_string("k3y");
+                cipher := stdlib.bytes.xor_cipher(plain, key);
+                restored := stdlib.bytes.xor_cipher(cipher, key);
+                return (cipher, restored);
+                """
+            )
+            cipher = result[0].as_bytes()
+            self.assertEqual(len(cipher), len(b"attack at dawn"))
+            self.assertNotEqual(cipher, b"attack at dawn")
+            self.assertEqual(cipher[0], ord("a") ^ ord("k"))
+            self.assertEqual(result[1].as_bytes(), b"attack at dawn")
+
+        asyncio.run(test())
+
+    def test_extra_stdlib(self):
+        def shout(self_object: PyOnionObject, arguments: PyOnionObject):
+            return arguments.text.as_string().upper()
+
+        extra = {
+            "host": PyOnionObject(
+                {
+                    "shout": wrap_py_function(
+                        PyOnionObject([PyOnionObject.named("text", None)]),
+                        "<python>::shout",
+                        shout,
+                        None,
+                        None,
+                    ),
+                }
+            ),
+            "answer": 42,
+        }
+        code = """
+            @required stdlib;
+            return (stdlib.host.shout("hi"), stdlib.answer, stdlib.math.abs(-1));
+            """
+
+        async def test():
+            result = await eval_or_throw(code, extra_stdlib=extra)
+            self.assertEqual(result[0].as_string(), "HI")
+            self.assertEqual(result[1].as_integer(), 42)
+            self.assertEqual(result[2].as_integer(), 1)
+
+        asyncio.run(test())
+
+        result = eval_sync(code, extra_stdlib=extra)
+        self.assertTrue(result.key().as_boolean())
+
+        with self.assertRaises(RuntimeError):
+            eval_sync("return 1;", extra_stdlib={"math": 1})
+
+    def test_rate_limiter(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                limiter := stdlib.time.rate_limiter(2);
+                return (
+                    limiter.try_acquire(),
+                    limiter.try_acquire(),
+                    limiter.try_acquire(),
+                    limiter.try_acquire(),
+                    limiter.try_acquire()
+                );
+                """
+            )
+            granted = [result[i].as_boolean() for i in range(5)]
+            self.assertEqual(granted, [True, True, False, False, False])
+
+        asyncio.run(test())
+
+    def test_json(self):
+        code = """
+            @required stdlib;
+            @required text;
+            doc := stdlib.json.parse(text);
+            return (
+                doc.name,
+                doc.tags,
+                stdlib.json.stringify((a => 1, b => ("x", null, false)))
+            );
+            """
+
+        async def test():
+            result = await eval_or_throw(
+                code,
+                None,
+                [
+                    PyOnionObject.named(
+                        "text", '{"name": "onion", "tags": [1, 2.5, true, null]}'
+                    )
+                ],
+            )
+            self.assertEqual(result[0].as_string(), "onion")
+            tags = result[1]
+            self.assertEqual(tags[0].as_integer(), 1)
+            self.assertEqual(tags[1].as_float(), 2.5)
+            self.assertTrue(tags[2].as_boolean())
+            self.assertTrue(tags[3].is_null())
+            self.assertEqual(result[2].as_string(), '{"a":1,"b":["x",null,false]}')
+
+            with self.assertRaises(OnionRuntimeError):
+                await eval_or_throw(
+                    code, None, [PyOnionObject.named("text", "{not json")]
+                )
+
+        asyncio.run(test())
+
+    def test_json_path(self):
+        data = {"a": {"b": [{"c": 1}, {"c": 2}]}}
+
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required data;
+                return (
+                    stdlib.types.json_path(data, "a.b[1].c"),
+                    stdlib.types.json_path(data, "a.b[5].c"),
+                    stdlib.types.json_path(data, "a.missing")
+                );
+                """,
+                None,
+                PyOnionObject({"data": data}),
+            )
+            self.assertEqual(result[0].as_integer(), 2)
+            self.assertTrue(result[1].is_null())
+            self.assertTrue(result[2].is_null())
+
+        asyncio.run(test())
+
+    def test_similarity_ratio(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return (
+                    stdlib.string.similarity_ratio("onion", "onion"),
+                    stdlib.string.similarity_ratio("abcd", "wxyz"),
+                    stdlib.string.similarity_ratio("", "")
+                );
+                """
+            )
+            self.assertEqual(result[0].as_float(), 1.0)
+            self.assertLess(result[1].as_float(), 0.1)
+            self.assertEqual(result[2].as_float(), 1.0)
+
+        asyncio.run(test())
+
+    def test_dot_norm(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return (
+                    stdlib.math.dot((1, 2, 3), (4, 5, 6)),
+                    stdlib.math.norm((3, 4))
+                );
+                """
+            )
+            self.assertEqual(result[0].as_integer(), 32)
+            self.assertEqual(result[1].as_float(), 5.0)
+
+            with self.assertRaises(OnionRuntimeError):
+                await eval_or_throw(
+                    """
+                    @required stdlib;
+                    @required big;
+                    return stdlib.math.dot(big, big);
+                    """,
+                    None,
+                    PyOnionObject({"big": [9223372036854775807, 2]}),
+                )
+
+        asyncio.run(test())
+
+    def test_measure(self):
+        def slow_double(self_object: PyOnionObject, arguments: PyOnionObject):
+            time.sleep(0.02)
+            return arguments.x.as_integer() * 2
+
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required slow_double;
+                return stdlib.time.measure(slow_double, 5);
+                """,
+                None,
+                [
+                    PyOnionObject.named(
+                        "slow_double",
+                        wrap_py_function(
+                            PyOnionObject([PyOnionObject.named("x", None)]),
+                            "<python>::slow_double",
+                            slow_double,
+                            None,
+                            None,
+                        ),
+                    )
+                ],
+            )
+            self.assertTrue(result.is_pair())
+            self.assertEqual(result.key().as_integer(), 10)
+            self.assertGreaterEqual(result.value().as_float(), 15.0)
+            self.assertLess(result.value().as_float(), 5000.0)
+
+        asyncio.run(test())
+
+    def test_validate(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                schema := (
+                    name => stdlib.types.type_of("text"),
+                    age => stdlib.types.type_of(1)
+                );
+                @required partial;
+                return (
+                    stdlib.types.validate((name => "onion", age => 3), schema),
+                    stdlib.types.validate(partial, schema)
+                );
+                """,
+                None,
+                PyOnionObject({"partial": {"name": "onion"}}),
+            )
+            self.assertEqual(result[0].to_py(), [])
+            problems = result[1].to_py()
+            self.assertEqual(len(problems), 1)
+            self.assertIn("age", problems[0])
+
+        asyncio.run(test())
+
+    def test_hex_escape(self):
+        text = "tab\there\x01\\é"
+
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required text;
+                escaped := stdlib.string.hex_escape(text);
+                return (escaped, stdlib.string.hex_unescape(escaped));
+                """,
+                None,
+                PyOnionObject({"text": text}),
+            )
+            self.assertEqual(result[0].as_string(), "tab\\x09here\\x01\\x5c\\xc3\\xa9")
+            self.assertEqual(result[1].as_string(), text)
+
+        asyncio.run(test())
+
+    def test_transpose(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return stdlib.types.tuple.transpose(((1, 2, 3), (4, 5, 6)));
+                """
+            )
+            self.assertEqual(result.to_py(), [[1, 4], [2, 5], [3, 6]])
+
+            with self.assertRaises(OnionRuntimeError):
+                await eval_or_throw(
+                    """
+                    @required stdlib;
+                    return stdlib.types.tuple.transpose(((1, 2), (3, 4, 5)));
+                    """
+                )
+
+        asyncio.run(test())
+
+    def test_angle_diff(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return (
+                    stdlib.math.angle_diff(350, 10),
+                    stdlib.math.angle_diff(10, 350),
+                    stdlib.math.angle_diff(0, 180),
+                    stdlib.math.clamp_angle(370)
+                );
+                """
+            )
+            self.assertEqual(result[0].as_integer(), 20)
+            self.assertEqual(result[1].as_integer(), -20)
+            self.assertEqual(result[2].as_integer(), 180)
+            self.assertEqual(result[3].as_integer(), 10)
+
+        asyncio.run(test())
+
+    def test_bigint(self):
+        big = "9223372036854775807"
+        bigger = "18446744073709551616"
+
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required big;
+                @required bigger;
+                return (
+                    stdlib.bigint.add(big, 1),
+                    stdlib.bigint.sub(bigger, big),
+                    stdlib.bigint.mul(big, big),
+                    stdlib.bigint.div(bigger, "3"),
+                    stdlib.bigint.compare(bigger, big),
+                    stdlib.bigint.compare(big, bigger),
+                    stdlib.bigint.compare(bigger, bigger)
+                );
+                """,
+                None,
+                PyOnionObject({"big": big, "bigger": bigger}),
+            )
+            self.assertEqual(result[0].as_string(), str(int(big) + 1))
+            self.assertEqual(result[1].as_string(), str(int(bigger) - int(big)))
+            self.assertEqual(result[2].as_string(), str(int(big) * int(big)))
+            self.assertEqual(result[3].as_string(), str(int(bigger) // 3))
+            self.assertEqual(result[4].as_integer(), 1)
+            self.assertEqual(result[5].as_integer(), -1)
+            self.assertEqual(result[6].as_integer(), 0)
+
+        asyncio.run(test())
+
+    def test_string_diff(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required old;
+                @required new;
+                return stdlib.string.diff(old, new);
+                """,
+                None,
+                PyOnionObject({"old": "keep\nold line", "new": "keep\nnew line"}),
+            )
+            self.assertEqual(result.to_py(), [" keep", "-old line", "+new line"])
+
+        asyncio.run(test())
+
+    def test_enum_from(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                colors := stdlib.types.enum_from(("A", "B", "C"));
+                return (colors.A, colors.B, colors.C);
+                """
+            )
+            self.assertEqual(result.to_py(), [0, 1, 2])
+
+        asyncio.run(test())
+
+    def test_solve_quadratic(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return (
+                    stdlib.math.solve_quadratic(1, 0 - 3, 2),
+                    stdlib.math.solve_quadratic(1, 0 - 2, 1),
+                    stdlib.math.solve_quadratic(1, 0, 1)
+                );
+                """
+            )
+            self.assertEqual(result[0].to_py(), [1.0, 2.0])
+            self.assertEqual(result[1].to_py(), [1.0])
+            self.assertEqual(result[2].to_py(), [])
+
+            with self.assertRaises(OnionRuntimeError):
+                await eval_or_throw(
+                    """
+                    @required stdlib;
+                    return stdlib.math.solve_quadratic(0, 1, 1);
+                    """
+                )
+
+        asyncio.run(test())
+
+    def test_bytes_concat_all(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return stdlib.bytes.concat_all((
+                    stdlib.bytes.from_string("ab"),
+                    stdlib.bytes.from_string("cd"),
+                    stdlib.bytes.from_string("ef")
+                ));
+                """
+            )
+            self.assertEqual(result.as_bytes(), b"abcdef")
+
+            with self.assertRaises(OnionRuntimeError):
+                await eval_or_throw(
+                    """
+                    @required stdlib;
+                    return stdlib.bytes.concat_all((stdlib.bytes.from_string("ab"), 1));
+                    """
+                )
+
+        asyncio.run(test())
+
+    def test_string_concat_all(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return stdlib.string.concat_all(("on", "i", "on", "!"));
+                """
+            )
+            self.assertEqual(result.as_string(), "onion!")
+
+        asyncio.run(test())
+
+    def test_interp_table(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                xs := (0, 10, 20);
+                ys := (0, 100, 300);
+                return (
+                    stdlib.math.interp_table(15, xs, ys),
+                    stdlib.math.interp_table(25, xs, ys),
+                    stdlib.math.interp_table(0 - 5, xs, ys)
+                );
+                """
+            )
+            self.assertEqual(result.to_py(), [200.0, 300.0, 0.0])
+
+        asyncio.run(test())
+
+    def test_count_lines(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required text;
+                return (
+                    stdlib.string.count_lines(text),
+                    stdlib.string.line_at(text, 1)
+                );
+                """,
+                None,
+                PyOnionObject({"text": "first\nsecond\nthird"}),
+            )
+            self.assertEqual(result[0].as_integer(), 3)
+            self.assertEqual(result[1].as_string(), "second")
+
+            with self.assertRaises(OnionRuntimeError):
+                await eval_or_throw(
+                    """
+                    @required stdlib;
+                    return stdlib.string.line_at("only", 3);
+                    """
+                )
+
+        asyncio.run(test())
+
+    def test_snap(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return (
+                    stdlib.math.snap(2.9999999, 0.000001),
+                    stdlib.math.snap(2.5, 0.000001)
+                );
+                """
+            )
+            self.assertEqual(result[0].as_float(), 3.0)
+            self.assertEqual(result[1].as_float(), 2.5)
+
+        asyncio.run(test())
+
+    def test_common_prefix_len(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return (
+                    stdlib.bytes.common_prefix_len(
+                        stdlib.bytes.from_string("abcXY"),
+                        stdlib.bytes.from_string("abcZ")
+                    ),
+                    stdlib.bytes.common_prefix_len(
+                        stdlib.bytes.from_string("x"),
+                        stdlib.bytes.from_string("y")
+                    )
+                );
+                """
+            )
+            self.assertEqual(result.to_py(), [3, 0])
+
+        asyncio.run(test())
+
+    def test_ascii_only(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required text;
+                return (
+                    stdlib.string.ascii_only(text),
+                    stdlib.string.ascii_only(text, "?")
+                );
+                """,
+                None,
+                PyOnionObject({"text": "café_naïve"}),
+            )
+            self.assertEqual(result[0].as_string(), "caf_nave")
+            self.assertEqual(result[1].as_string(), "caf?_na?ve")
+
+        asyncio.run(test())
+
+    def test_wrap(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required low;
+                @required high;
+                return (
+                    stdlib.math.wrap(370, 0, 360),
+                    stdlib.math.wrap(0 - 10, 0, 360),
+                    stdlib.math.wrap(high, low, high)
+                );
+                """,
+                None,
+                PyOnionObject({"low": -(2**63), "high": 2**63 - 1}),
+            )
+            self.assertEqual(result[0].as_integer(), 10)
+            self.assertEqual(result[1].as_integer(), 350)
+            self.assertEqual(result[2].as_integer(), -(2**63))
+
+        asyncio.run(test())
+
+    def test_types_pretty(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required data;
+                return stdlib.types.pretty(data, 2);
+                """,
+                None,
+                PyOnionObject({"data": {"a": {"b": 1}}}),
+            )
+            self.assertEqual(result.as_string(), '(\n  "a" => (\n    "b" => 1,\n  ),\n)')
+
+        asyncio.run(test())
+
+    def test_to_bytes_slice(self):
+        code = """
+            @required stdlib;
+            @required text;
+            @required start;
+            @required end;
+            return stdlib.string.to_bytes_slice(text, start, end);
+            """
+
+        async def test():
+            result = await eval_or_throw(
+                code, None, PyOnionObject({"text": "aé b", "start": 1, "end": 3})
+            )
+            self.assertEqual(result.as_bytes(), "é".encode("utf-8"))
+
+            with self.assertRaises(OnionRuntimeError):
+                await eval_or_throw(
+                    code, None, PyOnionObject({"text": "aé b", "start": 2, "end": 4})
+                )
+
+        asyncio.run(test())
+
+    def test_window(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return (
+                    stdlib.types.tuple.window((1, 2, 3, 4), 2),
+                    stdlib.types.tuple.window((1, 2), 3)
+                );
+                """
+            )
+            self.assertEqual(result[0].to_py(), [[1, 2], [2, 3], [3, 4]])
+            self.assertEqual(result[1].to_py(), [])
+
+            with self.assertRaises(OnionRuntimeError):
+                await eval_or_throw(
+                    """
+                    @required stdlib;
+                    return stdlib.types.tuple.window((1, 2), 0);
+                    """
+                )
+
+        asyncio.run(test())
+
+    def test_truncate(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return (
+                    stdlib.string.truncate("hello world", 8),
+                    stdlib.string.truncate("short", 8),
+                    stdlib.string.truncate("hello world", 6, "~")
+                );
+                """
+            )
+            self.assertEqual(result.to_py(), ["hello...", "short", "hello~"])
+
+        asyncio.run(test())
+
+    def test_format_duration(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return (
+                    stdlib.time.format_duration(0),
+                    stdlib.time.format_duration(250),
+                    stdlib.time.format_duration(3723000),
+                    stdlib.time.format_duration(90061001)
+                );
+                """
+            )
+            self.assertEqual(result.to_py(), ["0ms", "250ms", "1h 2m 3s", "1d 1h 1m 1s 1ms"])
+
+        asyncio.run(test())
+
+    def test_to_string_lossy(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required data;
+                return stdlib.bytes.to_string_lossy(data);
+                """,
+                None,
+                PyOnionObject({"data": b"ok\xff\xfe!"}),
+            )
+            self.assertEqual(result.as_string(), "ok\ufffd\ufffd!")
+
+        asyncio.run(test())
+
+    def test_clamp_index(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required lowest;
+                return (
+                    stdlib.math.clamp_index(0 - 1, 5),
+                    stdlib.math.clamp_index(0 - 9, 5),
+                    stdlib.math.clamp_index(9, 5),
+                    stdlib.math.clamp_index(2, 5),
+                    stdlib.math.clamp_index(lowest, 5)
+                );
+                """,
+                None,
+                PyOnionObject({"lowest": -(2**63)}),
+            )
+            self.assertEqual(result.to_py(), [4, 0, 4, 2, 0])
+
+        asyncio.run(test())
+
+    def test_is_empty(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required empty_tuple;
+                @required empty_bytes;
+                return (
+                    stdlib.types.is_empty(""),
+                    stdlib.types.is_empty(empty_bytes),
+                    stdlib.types.is_empty(empty_tuple),
+                    stdlib.types.is_empty(null),
+                    stdlib.types.is_empty("x"),
+                    stdlib.types.is_empty(0),
+                    stdlib.types.is_empty((1, 2))
+                );
+                """,
+                None,
+                PyOnionObject({"empty_tuple": [], "empty_bytes": b""}),
+            )
+            self.assertEqual(
+                result.to_py(), [True, True, True, True, False, False, False]
+            )
+
+        asyncio.run(test())
+
+    def test_zfill(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return (
+                    stdlib.string.zfill("-5", 4),
+                    stdlib.string.zfill("+7", 3),
+                    stdlib.string.zfill("42", 5),
+                    stdlib.string.zfill("12345", 3)
+                );
+                """
+            )
+            self.assertEqual(result.to_py(), ["-005", "+07", "00042", "12345"])
+
+            with self.assertRaises(OnionRuntimeError):
+                await eval_or_throw(
+                    """
+                    @required stdlib;
+                    return stdlib.string.zfill("1", 9000000000000);
+                    """
+                )
+
+        asyncio.run(test())
+
+    def test_flat_map(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                twice := (n => 0) -> (n, n);
+                return stdlib.types.tuple.flat_map((1, 2, 3), twice);
+                """
+            )
+            self.assertEqual(result.to_py(), [1, 1, 2, 2, 3, 3])
+
+            with self.assertRaises(OnionRuntimeError):
+                await eval_or_throw(
+                    """
+                    @required stdlib;
+                    return stdlib.types.tuple.flat_map((1, 2), (n => 0) -> n);
+                    """
+                )
+
+        asyncio.run(test())
+
+    def test_poly_eval(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return (
+                    stdlib.math.poly_eval((1, 0, 0 - 1), 2),
+                    stdlib.math.poly_eval((2, 3), 1.5)
+                );
+                """
+            )
+            self.assertEqual(result[0].as_float(), 3.0)
+            self.assertEqual(result[1].as_float(), 6.0)
+
+        asyncio.run(test())
+
+    def test_count_chars(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                counts := stdlib.string.count_chars("aab");
+                return (counts, counts.a, counts.b);
+                """
+            )
+            self.assertEqual(result[0].as_dict(), {"a": 2, "b": 1})
+            self.assertEqual(result[1].as_integer(), 2)
+            self.assertEqual(result[2].as_integer(), 1)
+
+        asyncio.run(test())
+
+    def test_pad_to_multiple(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required data;
+                return stdlib.bytes.pad_to_multiple(data, 4, 0);
+                """,
+                None,
+                PyOnionObject({"data": b"\x01\x02\x03\x04\x05"}),
+            )
+            self.assertEqual(result.as_bytes(), b"\x01\x02\x03\x04\x05\x00\x00\x00")
+
+            with self.assertRaises(OnionRuntimeError):
+                await eval_or_throw(
+                    """
+                    @required stdlib;
+                    return stdlib.bytes.pad_to_multiple(stdlib.bytes.from_string("a"), 4, 256);
+                    """
+                )
+
+        asyncio.run(test())
+
+    def test_with_defaults(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required cfg;
+                return stdlib.types.with_defaults(cfg, (host => "localhost", port => 80));
+                """,
+                None,
+                PyOnionObject({"cfg": {"port": 9000}}),
+            )
+            self.assertEqual(result.as_dict(), {"host": "localhost", "port": 9000})
+
+        asyncio.run(test())
+
+    def test_clamp_each(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return stdlib.math.clamp_each((0 - 5, 3, 12, 7.5), 0, 10);
+                """
+            )
+            self.assertEqual(result.to_py(), [0, 3, 10, 7.5])
+
+            with self.assertRaises(OnionRuntimeError):
+                await eval_or_throw(
+                    """
+                    @required stdlib;
+                    return stdlib.math.clamp_each((1, "x"), 0, 10);
+                    """
+                )
+
+        asyncio.run(test())
+
+    def test_split_at(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return (
+                    stdlib.string.split_at("hello", 2),
+                    stdlib.string.split_at("hello", 99)
+                );
+                """
+            )
+            self.assertEqual(result[0].to_py(), ["he", "llo"])
+            self.assertEqual(result[1].to_py(), ["hello", ""])
+
+        asyncio.run(test())
+
+    def test_tuple_repeat(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return stdlib.types.tuple.repeat((1, 2), 3);
+                """
+            )
+            self.assertEqual(result.to_py(), [1, 2, 1, 2, 1, 2])
+
+            with self.assertRaises(OnionRuntimeError):
+                await eval_or_throw(
+                    """
+                    @required stdlib;
+                    return stdlib.types.tuple.repeat((1, 2), 9000000000000000000);
+                    """
+                )
+
+        asyncio.run(test())
+
+    def test_clamp_point(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return stdlib.math.clamp_point((0 - 5, 15), (0, 0), (10, 10));
+                """
+            )
+            self.assertEqual(result.to_py(), [0.0, 10.0])
+
+        asyncio.run(test())
+
+    def test_common_prefix(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return (
+                    stdlib.string.common_prefix(("foobar", "foobaz", "foox")),
+                    stdlib.string.common_suffix(("testing", "running", "sing")),
+                    stdlib.string.common_prefix(("abc", "xyz"))
+                );
+                """
+            )
+            self.assertEqual(result.to_py(), ["foo", "ing", ""])
+
+        asyncio.run(test())
+
+    def test_bytes_find_all(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return stdlib.bytes.find_all(
+                    stdlib.bytes.from_string("abXabYabab"),
+                    stdlib.bytes.from_string("ab")
+                );
+                """
+            )
+            self.assertEqual(result.to_py(), [0, 3, 6, 8])
+
+            with self.assertRaises(OnionRuntimeError):
+                await eval_or_throw(
+                    """
+                    @required stdlib;
+                    return stdlib.bytes.find_all(
+                        stdlib.bytes.from_string("ab"),
+                        stdlib.bytes.from_string("")
+                    );
+                    """
+                )
+
+        asyncio.run(test())
+
+    def test_accumulate(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return stdlib.math.accumulate((1, 2, 3, 4), (acc => 0, value => 0) -> acc + value, 0);
+                """
+            )
+            self.assertEqual(result.to_py(), [1, 3, 6, 10])
+
+        asyncio.run(test())
+
+    def test_tuple_scan(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return stdlib.types.tuple.scan((1, 2, 3), (state => 0, element => 0) -> state + element, 10);
+                """
+            )
+            self.assertEqual(result.to_py(), [10, 11, 13, 16])
+
+        asyncio.run(test())
+
+    def test_split_any(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return (
+                    stdlib.string.split_any("a,b;c", ",;"),
+                    stdlib.string.split_any(",,a;;b,", ",;")
+                );
+                """
+            )
+            self.assertEqual(result.to_py(), [["a", "b", "c"], ["a", "b"]])
+
+        asyncio.run(test())
+
+    def test_deep_get(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required data;
+                return (
+                    stdlib.types.deep_get(data, ("a", "b"), null),
+                    stdlib.types.deep_get(data, ("a", "missing"), "fallback"),
+                    stdlib.types.deep_get(data, ("list", 1), null)
+                );
+                """,
+                None,
+                PyOnionObject({"data": {"a": {"b": 42}, "list": [10, 20]}}),
+            )
+            self.assertEqual(result[0].as_integer(), 42)
+            self.assertEqual(result[1].as_string(), "fallback")
+            self.assertEqual(result[2].as_integer(), 20)
+
+        asyncio.run(test())
+
+    def test_map_range(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return (
+                    stdlib.math.map_range(5, 0, 10, 0, 100),
+                    stdlib.math.map_range(15, 0, 10, 0, 100),
+                    stdlib.math.map_range(15, 0, 10, 0, 100, true)
+                );
+                """
+            )
+            self.assertAlmostEqual(result[0].as_float(), 50.0)
+            self.assertAlmostEqual(result[1].as_float(), 150.0)
+            self.assertAlmostEqual(result[2].as_float(), 100.0)
+
+            with self.assertRaises(OnionRuntimeError):
+                await eval_or_throw(
+                    """
+                    @required stdlib;
+                    return stdlib.math.map_range(5, 1, 1, 0, 100);
+                    """
+                )
+
+        asyncio.run(test())
+
+    def test_intersperse(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required empty;
+                return (
+                    stdlib.types.tuple.intersperse((1, 2, 3), 0),
+                    stdlib.types.tuple.intersperse(empty, 0)
+                );
+                """,
+                None,
+                PyOnionObject({"empty": []}),
+            )
+            self.assertEqual(result.to_py(), [[1, 0, 2, 0, 3], []])
+
+        asyncio.run(test())
+
+    def test_words(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required sentence;
+                return (
+                    stdlib.string.word_count(sentence),
+                    stdlib.string.words(sentence)
+                );
+                """,
+                None,
+                PyOnionObject({"sentence": "  the   quick\tbrown \n fox  "}),
+            )
+            self.assertEqual(result[0].as_integer(), 4)
+            self.assertEqual(result[1].to_py(), ["the", "quick", "brown", "fox"])
+
+        asyncio.run(test())
+
+    def test_bytes_rotate(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                data := stdlib.bytes.from_string("abcde");
+                return (
+                    stdlib.bytes.rotate_left(data, 2),
+                    stdlib.bytes.rotate_right(data, 2),
+                    stdlib.bytes.rotate_left(data, 7),
+                    stdlib.bytes.rotate_right(stdlib.bytes.from_string(""), 3)
+                );
+                """
+            )
+            self.assertEqual(result[0].as_bytes(), b"cdeab")
+            self.assertEqual(result[1].as_bytes(), b"deabc")
+            self.assertEqual(result[2].as_bytes(), b"cdeab")
+            self.assertEqual(result[3].as_bytes(), b"")
+
+        asyncio.run(test())
+
+    def test_is_close(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return (
+                    stdlib.math.is_close(0.1 + 0.2, 0.3),
+                    stdlib.math.is_close(1.0, 1.1),
+                    stdlib.math.is_close(1.0, 1.05, 0.1)
+                );
+                """
+            )
+            self.assertEqual(result.to_py(), [True, False, True])
+
+        asyncio.run(test())
+
+    def test_expand_tabs(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required text;
+                return (
+                    stdlib.string.expand_tabs(text, 4),
+                    stdlib.string.expand_tabs(text)
+                );
+                """,
+                None,
+                PyOnionObject({"text": "a\tbc\tdefg\tx\nab\ty"}),
+            )
+            self.assertEqual(result[0].as_string(), "a\tbc\tdefg\tx\nab\ty".expandtabs(4))
+            self.assertEqual(result[1].as_string(), "a\tbc\tdefg\tx\nab\ty".expandtabs(8))
+
+        asyncio.run(test())
+
+    def test_safe_div(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return (
+                    stdlib.math.safe_div(7, 2, 0),
+                    stdlib.math.safe_div(7, 0, 0 - 1),
+                    stdlib.math.safe_div(1.0, 0.0, "none"),
+                    stdlib.math.safe_div(1.0, 4)
+                );
+                """
+            )
+            self.assertEqual(result[0].as_integer(), 3)
+            self.assertEqual(result[1].as_integer(), -1)
+            self.assertEqual(result[2].as_string(), "none")
+            self.assertAlmostEqual(result[3].as_float(), 0.25)
+
+        asyncio.run(test())
+
+    def test_dedup_consecutive(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return stdlib.types.tuple.dedup_consecutive((1, 1, 2, 1));
+                """
+            )
+            self.assertEqual(result.to_py(), [1, 2, 1])
+
+        asyncio.run(test())
+
+    def test_mask(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return (
+                    stdlib.string.mask("4111222233334444", 0, 4, "*"),
+                    stdlib.string.mask("secret", 1, 1, "#"),
+                    stdlib.string.mask("abc", 2, 2, "*")
+                );
+                """
+            )
+            self.assertEqual(result.to_py(), ["************4444", "s####t", "abc"])
+
+            with self.assertRaises(OnionRuntimeError):
+                await eval_or_throw(
+                    """
+                    @required stdlib;
+                    return stdlib.string.mask("secret", 1, 1, "**");
+                    """
+                )
+
+        asyncio.run(test())
+
+    def test_popcount(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required data;
+                return stdlib.bytes.popcount(data);
+                """,
+                None,
+                PyOnionObject({"data": b"\xff\x0f\x01\x00"}),
+            )
+            self.assertEqual(result.as_integer(), 13)
+
+        asyncio.run(test())
+
+    def test_switch(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                cases := ((1, "one"), (2, "two"));
+                return (
+                    stdlib.types.switch(2, cases, "other"),
+                    stdlib.types.switch(5, cases, "other")
+                );
+                """
+            )
+            self.assertEqual(result.to_py(), ["two", "other"])
+
+        asyncio.run(test())
+
+    def test_moving_average(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return stdlib.math.moving_average((1, 2, 3, 4, 5), 3);
+                """
+            )
+            self.assertEqual(result.to_py(), [2.0, 3.0, 4.0])
+
+            for window in ("0", "6"):
+                with self.assertRaises(OnionRuntimeError):
+                    await eval_or_throw(
+                        f"""
+                        @required stdlib;
+                        return stdlib.math.moving_average((1, 2, 3, 4, 5), {window});
+                        """
+                    )
+
+        asyncio.run(test())
+
+    def test_display_width(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required text;
+                return (stdlib.string.display_width("abc"), stdlib.string.display_width(text));
+                """,
+                None,
+                PyOnionObject({"text": "ab漢字"}),
+            )
+            self.assertEqual(result.to_py(), [3, 6])
+
+        asyncio.run(test())
+
+    def test_narrow_integers(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return (
+                    stdlib.math.to_i32(0 - 2147483648),
+                    stdlib.math.to_i32(2147483647),
+                    stdlib.math.to_u8(0),
+                    stdlib.math.to_u8(255)
+                );
+                """
+            )
+            self.assertEqual(result.to_py(), [-2147483648, 2147483647, 0, 255])
+
+            for call in (
+                "stdlib.math.to_i32(2147483648)",
+                "stdlib.math.to_i32(0 - 2147483649)",
+                "stdlib.math.to_u8(256)",
+                "stdlib.math.to_u8(0 - 1)",
+            ):
+                with self.assertRaises(OnionRuntimeError):
+                    await eval_or_throw(
+                        f"""
+                        @required stdlib;
+                        return {call};
+                        """
+                    )
+
+        asyncio.run(test())
+
+    def test_uri_component(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required text;
+                encoded := stdlib.string.encode_uri_component(text);
+                return (encoded, stdlib.string.decode_uri_component(encoded));
+                """,
+                None,
+                PyOnionObject({"text": "a b&c/d=é~*"}),
+            )
+            self.assertEqual(result[0].as_string(), "a%20b%26c%2Fd%3D%C3%A9~*")
+            self.assertEqual(result[1].as_string(), "a b&c/d=é~*")
+
+            with self.assertRaises(OnionRuntimeError):
+                await eval_or_throw(
+                    """
+                    @required stdlib;
+                    return stdlib.string.decode_uri_component("bad%ZZ");
+                    """
+                )
+
+        asyncio.run(test())
+
+    def test_days_between(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return (
+                    stdlib.time.days_between(1700000000, 1700172800),
+                    stdlib.time.days_between(1700172800, 1700000000),
+                    stdlib.time.days_between(1700000000, 1700172799)
+                );
+                """
+            )
+            self.assertEqual(result.to_py(), [2, 2, 1])
+
+        asyncio.run(test())
+
+    def test_read_int_arrays(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required data;
+                return (
+                    stdlib.bytes.read_u16_array(data, false),
+                    stdlib.bytes.read_u16_array(data, true),
+                    stdlib.bytes.read_u32_array(data, false),
+                    stdlib.bytes.read_u32_array(data, true)
+                );
+                """,
+                None,
+                PyOnionObject({"data": b"\x01\x02\x03\x04"}),
+            )
+            self.assertEqual(
+                result.to_py(),
+                [[0x0201, 0x0403], [0x0102, 0x0304], [0x04030201], [0x01020304]],
+            )
+
+            with self.assertRaises(OnionRuntimeError):
+                await eval_or_throw(
+                    """
+                    @required stdlib;
+                    @required data;
+                    return stdlib.bytes.read_u16_array(data, false);
+                    """,
+                    None,
+                    PyOnionObject({"data": b"\x01\x02\x03"}),
+                )
+
+        asyncio.run(test())
+
+    def test_frexp_ldexp(self):
+        async def test():
+            for x in (8.0, 0.15625, -3.75, 5e-324):
+                parts = await eval_or_throw(
+                    """
+                    @required stdlib;
+                    @required x;
+                    return stdlib.math.frexp(x);
+                    """,
+                    None,
+                    PyOnionObject({"x": x}),
+                )
+                self.assertTrue(parts.is_pair())
+                mantissa, exponent = parts.key().as_float(), parts.value().as_integer()
+                self.assertEqual((mantissa, exponent), math.frexp(x))
+
+                result = await eval_or_throw(
+                    """
+                    @required stdlib;
+                    @required x;
+                    parts := stdlib.math.frexp(x);
+                    return (
+                        stdlib.math.ldexp(parts),
+                        stdlib.math.ldexp(parts.key, parts.value)
+                    );
+                    """,
+                    None,
+                    PyOnionObject({"x": x}),
+                )
+                self.assertEqual(result.to_py(), [x, x])
+
+        asyncio.run(test())
+
+    def test_splitlines_keepends(self):
+        async def test():
+            text = "first\nsecond\r\nthird"
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required text;
+                return stdlib.string.splitlines_keepends(text);
+                """,
+                None,
+                PyOnionObject({"text": text}),
+            )
+            lines = result.to_py()
+            self.assertEqual(lines, ["first\n", "second\r\n", "third"])
+            self.assertEqual("".join(lines), text)
+
+        asyncio.run(test())
+
+    def test_bytes_sub(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                data := stdlib.bytes.from_string("abcdef");
+                return (
+                    stdlib.bytes.sub(data, 1, 0 - 1),
+                    stdlib.bytes.sub(data, 0 - 2),
+                    stdlib.bytes.sub(data, 4, 100),
+                    stdlib.bytes.sub(data, 4, 2)
+                );
+                """
+            )
+            self.assertEqual(result[0].as_bytes(), b"bcde")
+            self.assertEqual(result[1].as_bytes(), b"ef")
+            self.assertEqual(result[2].as_bytes(), b"ef")
+            self.assertEqual(result[3].as_bytes(), b"")
+
+        asyncio.run(test())
+
+    def test_clamp_nonneg(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return (
+                    stdlib.math.clamp_nonneg(0 - 5),
+                    stdlib.math.clamp_nonneg(7),
+                    stdlib.math.clamp_positive(0 - 2.5, 0.5),
+                    stdlib.math.clamp_positive(3, 1)
+                );
+                """
+            )
+            self.assertEqual(result[0].as_integer(), 0)
+            self.assertEqual(result[1].as_integer(), 7)
+            self.assertAlmostEqual(result[2].as_float(), 0.5)
+            self.assertEqual(result[3].as_integer(), 3)
+
+        asyncio.run(test())
+
+    def test_longest_common_substring(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return (
+                    stdlib.string.longest_common_substring("xabcdey", "zzbcdezz"),
+                    stdlib.string.longest_common_substring("abc", "xyz")
+                );
+                """
+            )
+            self.assertEqual(result.to_py(), ["bcde", ""])
+
+        asyncio.run(test())
+
+    def test_tuple_rotate(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return (
+                    stdlib.types.tuple.rotate((1, 2, 3, 4), 1),
+                    stdlib.types.tuple.rotate((1, 2, 3, 4), 0 - 1),
+                    stdlib.types.tuple.rotate((1, 2, 3, 4), 6)
+                );
+                """
+            )
+            self.assertEqual(result.to_py(), [[2, 3, 4, 1], [4, 1, 2, 3], [3, 4, 1, 2]])
+
+        asyncio.run(test())
+
+    def test_binomial(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return (
+                    stdlib.math.binomial(5, 2),
+                    stdlib.math.binomial(10, 0),
+                    stdlib.math.binomial(60, 30)
+                );
+                """
+            )
+            self.assertEqual(result.to_py(), [10, 1, math.comb(60, 30)])
+
+            for call in ("binomial(3, 5)", "binomial(0 - 1, 0)", "binomial(200, 100)"):
+                with self.assertRaises(OnionRuntimeError):
+                    await eval_or_throw(
+                        f"""
+                        @required stdlib;
+                        return stdlib.math.{call};
+                        """
+                    )
+
+        asyncio.run(test())
+
+    def test_trim_chars(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return (
+                    stdlib.string.trim_chars("**hi**", "*"),
+                    stdlib.string.trim_chars("-+-a-b+-", "+-"),
+                    stdlib.string.trim_chars("hi", "")
+                );
+                """
+            )
+            self.assertEqual(result.to_py(), ["hi", "a-b", "hi"])
+
+        asyncio.run(test())
+
+    def test_checksums(self):
+        async def test():
+            data = bytes(range(256)) * 2
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required data;
+                return (stdlib.bytes.checksum8(data), stdlib.bytes.checksum16(data));
+                """,
+                None,
+                PyOnionObject({"data": data}),
+            )
+            self.assertEqual(result.to_py(), [sum(data) % 256, sum(data) % 65536])
+            self.assertEqual(sum(data) % 65536, 65280)
+
+        asyncio.run(test())
+
+    def test_stats(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return stdlib.math.stats((4, 1, 7, 2.5));
+                """
+            )
+            self.assertEqual(
+                result.as_dict(),
+                {"count": 4, "min": 1.0, "max": 7.0, "sum": 14.5, "mean": 3.625},
+            )
+
+            with self.assertRaises(OnionRuntimeError):
+                await eval_or_throw(
+                    """
+                    @required stdlib;
+                    @required empty;
+                    return stdlib.math.stats(empty);
+                    """,
+                    None,
+                    PyOnionObject({"empty": []}),
+                )
+
+        asyncio.run(test())
+
+    def test_justify(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                return (
+                    stdlib.string.justify("ab", 6, "left", "."),
+                    stdlib.string.justify("ab", 6, "right", "."),
+                    stdlib.string.justify("ab", 7, "center", "."),
+                    stdlib.string.justify("abcdef", 3, "left", ".")
+                );
+                """
+            )
+            self.assertEqual(result.to_py(), ["ab....", "....ab", "..ab...", "abcdef"])
+
+            with self.assertRaises(OnionRuntimeError):
+                await eval_or_throw(
+                    """
+                    @required stdlib;
+                    return stdlib.string.justify("ab", 6, "middle", ".");
+                    """
+                )
+
+        asyncio.run(test())
+
+    def test_import_py_builtin(self):
+        async def test():
+            context = import_py("builtins", ["max"])
+            result = await eval_or_throw(
+                """
+                @required max;
+                return max((3, 9, 4));
+                """,
+                None,
+                context,
+            )
+            self.assertEqual(result.as_integer(), 9)
+
+        asyncio.run(test())
+
+    def test_memoize_distinguishes_types(self):
+        calls = []
+
+        def describe(self_object: PyOnionObject, arguments: PyOnionObject):
+            value = arguments.x.to_py()
+            calls.append(value)
+            return type(value).__name__
+
+        async def test():
+            context = [
+                PyOnionObject.named(
+                    "describe",
+                    wrap_py_function(
+                        PyOnionObject([PyOnionObject.named("x", None)]),
+                        "<python>::describe",
+                        describe,
+                        None,
+                        None,
+                    ),
+                ),
+            ]
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required describe;
+                cached := stdlib.types.memoize(describe);
+                return (cached(3), cached("3"), cached(3), cached("3"));
+                """,
+                None,
+                context,
+            )
+            self.assertEqual(result.to_py(), ["int", "str", "int", "str"])
+            self.assertEqual(calls, [3, "3"])
+
+        asyncio.run(test())
+
+    def test_rate_limiter_fractional_rate(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                limiter := stdlib.time.rate_limiter(0.5);
+                return (limiter.try_acquire(), limiter.try_acquire());
+                """
+            )
+            self.assertEqual(result.to_py(), [True, False])
+
+        asyncio.run(test())
+
+    def test_json_key_order(self):
+        async def test():
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required text;
+                doc := stdlib.json.parse(text);
+                return (doc, stdlib.json.stringify(doc));
+                """,
+                None,
+                PyOnionObject({"text": '{"zeta": 1, "alpha": [{"b": 2, "a": 3}], "mid": null}'}),
+            )
+            self.assertEqual(list(result[0].as_dict()), ["zeta", "alpha", "mid"])
+            self.assertEqual(
+                result[1].as_string(), '{"zeta":1,"alpha":[{"b":2,"a":3}],"mid":null}'
+            )
+
+        asyncio.run(test())
+
+    def test_angle_diff_extremes(self):
+        async def test():
+            lo, hi = -(2**63), 2**63 - 1
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required lo;
+                @required hi;
+                return (
+                    stdlib.math.angle_diff(lo, 1),
+                    stdlib.math.angle_diff(hi, lo),
+                    stdlib.math.angle_diff(lo, hi)
+                );
+                """,
+                None,
+                PyOnionObject({"lo": lo, "hi": hi}),
+            )
+
+            def expected(a, b):
+                diff = (b - a) % 360
+                return diff - 360 if diff > 180 else diff
+
+            self.assertEqual(
+                result.to_py(), [expected(lo, 1), expected(hi, lo), expected(lo, hi)]
+            )
+
+        asyncio.run(test())
+
+    def test_clamp_each_invalid_bounds(self):
+        async def test():
+            for low, high in ((2**53 + 1, 2**53), (float("nan"), 1.0), (0, float("nan"))):
+                with self.assertRaises(OnionRuntimeError):
+                    await eval_or_throw(
+                        """
+                        @required stdlib;
+                        @required low;
+                        @required high;
+                        return stdlib.math.clamp_each((1, 2, 3), low, high);
+                        """,
+                        None,
+                        PyOnionObject({"low": low, "high": high}),
+                    )
+
+            result = await eval_or_throw(
+                """
+                @required stdlib;
+                @required big;
+                return stdlib.math.clamp_each((0, big + 5), big, big + 1);
+                """,
+                None,
+                PyOnionObject({"big": 2**53}),
+            )
+            self.assertEqual(result.to_py(), [2**53, 2**53 + 1])
+
+        asyncio.run(test())
+
+    def test_clamp_point_nan_bounds(self):
+        async def test():
+            with self.assertRaises(OnionRuntimeError):
+                await eval_or_throw(
+                    """
+                    @required stdlib;
+                    @required nan;
+                    return stdlib.math.clamp_point((1, 1), (0, nan), (10, 10));
+                    """,
+                    None,
+                    PyOnionObject({"nan": float("nan")}),
+                )
+
+        asyncio.run(test())
+
+    def test_expand_tabs_too_large(self):
+        async def test():
+            with self.assertRaises(OnionRuntimeError):
+                await eval_or_throw(
+                    """
+                    @required stdlib;
+                    @required text;
+                    return stdlib.string.expand_tabs(text, 1152921504606846976);
+                    """,
+                    None,
+                    PyOnionObject({"text": "a\tb"}),
+                )
+
+        asyncio.run(test())
+
+    def test_justify_too_large(self):
+        async def test():
+            with self.assertRaises(OnionRuntimeError):
+                await eval_or_throw(
+                    """
+                    @required stdlib;
+                    return stdlib.string.justify("x", 1152921504606846976, "left", " ");
+                    """
+                )
+
+        asyncio.run(test())
+
+    def test_pad_to_multiple_too_large(self):
+        async def test():
+            with self.assertRaises(OnionRuntimeError):
+                await eval_or_throw(
+                    """
+                    @required stdlib;
+                    return stdlib.bytes.pad_to_multiple(
+                        stdlib.bytes.from_string("abc"), 1152921504606846976, 0
+                    );
+                    """
+                )
+
+        asyncio.run(test())
+
+    def test_string_diff_too_large(self):
+        async def test():
+            text = "\n".join(str(i) for i in range(3000))
+            with self.assertRaises(OnionRuntimeError):
+                await eval_or_throw(
+                    """
+                    @required stdlib;
+                    @required text;
+                    return stdlib.string.diff(text, text);
+                    """,
+                    None,
+                    PyOnionObject({"text": text}),
+                )
+
+        asyncio.run(test())
+
+    def test_partial_positional_arguments(self):
+        def subtract(self_object: PyOnionObject, arguments: PyOnionObject):
+            return arguments.a.as_integer() - arguments.b.as_integer()
+
+        async def test():
+            params = PyOnionObject(
+                [PyOnionObject.named("a", None), PyOnionObject.named("b", None)]
+            )
+            context = [
+                PyOnionObject.named(
+                    "subtract",
+                    wrap_py_function(
+                        params, "<python>::subtract", subtract, None, None
+                    ),
+                ),
+            ]
+            return await eval_or_throw(
+                """
+                @required stdlib;
+                @required subtract;
+                return stdlib.types.partial(subtract, (a => 10,));
+                """,
+                None,
+                context,
+            )
+
+        from_ten = asyncio.run(test())
+        with self.assertRaises(ValueError) as caught:
+            from_ten(2)
+        self.assertIn("only accept named arguments", caught.exception.args[0])
+
 
 if __name__ == "__main__":
     unittest.main()